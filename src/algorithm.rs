@@ -0,0 +1,163 @@
+use std::{fmt, str::FromStr};
+
+use knossos::maze::{
+    AldousBroder, Algorithm, Bias, BinaryTree, Eller, GrowingTree, HuntAndKill, Kruskal, Method,
+    Prim, RecursiveBacktracking, RecursiveDivision, Sidewinder,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GenerationAlgorithm {
+    Backtracking,
+    GrowingTree,
+    Prim,
+    Kruskal,
+    Eller,
+    Sidewinder,
+    BinaryTree,
+    HuntAndKill,
+    AldousBroder,
+    RecursiveDivision,
+}
+
+impl GenerationAlgorithm {
+    pub const ALL: [GenerationAlgorithm; 10] = [
+        GenerationAlgorithm::Backtracking,
+        GenerationAlgorithm::GrowingTree,
+        GenerationAlgorithm::Prim,
+        GenerationAlgorithm::Kruskal,
+        GenerationAlgorithm::Eller,
+        GenerationAlgorithm::Sidewinder,
+        GenerationAlgorithm::BinaryTree,
+        GenerationAlgorithm::HuntAndKill,
+        GenerationAlgorithm::AldousBroder,
+        GenerationAlgorithm::RecursiveDivision,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GenerationAlgorithm::Backtracking => "backtracking",
+            GenerationAlgorithm::GrowingTree => "growing-tree",
+            GenerationAlgorithm::Prim => "prim",
+            GenerationAlgorithm::Kruskal => "kruskal",
+            GenerationAlgorithm::Eller => "eller",
+            GenerationAlgorithm::Sidewinder => "sidewinder",
+            GenerationAlgorithm::BinaryTree => "binary-tree",
+            GenerationAlgorithm::HuntAndKill => "hunt-and-kill",
+            GenerationAlgorithm::AldousBroder => "aldous-broder",
+            GenerationAlgorithm::RecursiveDivision => "recursive-division",
+        }
+    }
+
+    pub fn build(self) -> Box<dyn Algorithm> {
+        match self {
+            GenerationAlgorithm::Backtracking => Box::new(RecursiveBacktracking),
+            GenerationAlgorithm::GrowingTree => Box::new(GrowingTree::new(Method::Random)),
+            GenerationAlgorithm::Prim => Box::new(Prim::new()),
+            GenerationAlgorithm::Kruskal => Box::new(Kruskal),
+            GenerationAlgorithm::Eller => Box::new(Eller),
+            GenerationAlgorithm::Sidewinder => Box::new(Sidewinder),
+            GenerationAlgorithm::BinaryTree => Box::new(BinaryTree::new(Bias::NorthWest)),
+            GenerationAlgorithm::HuntAndKill => Box::new(HuntAndKill::new()),
+            GenerationAlgorithm::AldousBroder => Box::new(AldousBroder),
+            GenerationAlgorithm::RecursiveDivision => Box::new(RecursiveDivision),
+        }
+    }
+}
+
+impl fmt::Display for GenerationAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for GenerationAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        GenerationAlgorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name() == s)
+            .ok_or_else(|| format!("unknown algorithm '{}'", s))
+    }
+}
+
+/// Weighted choice of generation algorithms, e.g. `backtracking=0.5,prim=0.3,kruskal=0.2`.
+/// Weights are relative and need not sum to one.
+#[derive(Clone, Debug)]
+pub struct AlgorithmMix {
+    entries: Vec<(GenerationAlgorithm, f64)>,
+}
+
+impl AlgorithmMix {
+    /// Picks an algorithm from an RNG seeded with the maze seed, so the same seed always
+    /// gets the same algorithm.
+    pub fn pick(&self, seed: u64) -> GenerationAlgorithm {
+        let total: f64 = self.entries.iter().map(|(_, weight)| weight).sum();
+        let mut target = StdRng::seed_from_u64(seed).random::<f64>() * total;
+        for &(algorithm, weight) in &self.entries {
+            if target < weight {
+                return algorithm;
+            }
+            target -= weight;
+        }
+        self.entries[self.entries.len() - 1].0
+    }
+}
+
+impl FromStr for AlgorithmMix {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut entries: Vec<(GenerationAlgorithm, f64)> = Vec::new();
+        for entry in s.split(',') {
+            let (name, weight) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected ALGORITHM=WEIGHT, got '{}'", entry))?;
+            let algorithm: GenerationAlgorithm = name.trim().parse()?;
+            let weight: f64 = weight
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid weight '{}' for {}", weight, algorithm))?;
+            if !weight.is_finite() || weight <= 0.0 {
+                return Err(format!("weight for {} must be positive", algorithm));
+            }
+            if entries.iter().any(|&(seen, _)| seen == algorithm) {
+                return Err(format!("{} is listed more than once", algorithm));
+            }
+            entries.push((algorithm, weight));
+        }
+        Ok(AlgorithmMix { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_roughly_match_the_weights() {
+        let mix: AlgorithmMix = "backtracking=0.5,prim=0.3,kruskal=0.2".parse().unwrap();
+        let draws = 10_000;
+        let share = |algorithm| {
+            (0..draws)
+                .filter(|&seed| mix.pick(seed) == algorithm)
+                .count() as f64
+                / draws as f64
+        };
+        assert!((share(GenerationAlgorithm::Backtracking) - 0.5).abs() < 0.03);
+        assert!((share(GenerationAlgorithm::Prim) - 0.3).abs() < 0.03);
+        assert!((share(GenerationAlgorithm::Kruskal) - 0.2).abs() < 0.03);
+        assert_eq!(mix.pick(42), mix.pick(42));
+    }
+
+    #[test]
+    fn rejects_invalid_weights() {
+        assert!("prim=0".parse::<AlgorithmMix>().is_err());
+        assert!("prim=-1".parse::<AlgorithmMix>().is_err());
+        assert!("prim=0.5,prim=0.5".parse::<AlgorithmMix>().is_err());
+        assert!("maze=1".parse::<AlgorithmMix>().is_err());
+    }
+}
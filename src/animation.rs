@@ -0,0 +1,158 @@
+use std::collections::{HashSet, VecDeque};
+
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Frame, Rgba, RgbaImage,
+};
+
+use crate::{
+    error::MazeError,
+    find_start,
+    retry::{write_file, RetryPolicy},
+    Maze,
+};
+
+const WALL: Rgba<u8> = Rgba([40, 40, 40, 255]);
+const FLOOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const VISITED: Rgba<u8> = Rgba([150, 190, 255, 255]);
+const SOLUTION: Rgba<u8> = Rgba([255, 200, 0, 255]);
+const START: Rgba<u8> = Rgba([0, 170, 0, 255]);
+const GOAL: Rgba<u8> = Rgba([220, 0, 0, 255]);
+
+pub struct GifOptions {
+    pub frame_delay_ms: u32,
+    pub scale: u32,
+}
+
+/// Runs a breadth-first search from the start and returns the cells reached at each
+/// depth, one layer per entry, stopping at the layer that contains the goal.
+pub fn bfs_layers(maze: &Maze) -> Vec<Vec<(usize, usize)>> {
+    let start = find_start(maze);
+    let mut visited = HashSet::with_capacity(maze.open_cell_count());
+    let mut layers = vec![vec![start]];
+    let mut queue = VecDeque::from([start]);
+    visited.insert(start);
+
+    while !queue.is_empty() {
+        if layers
+            .last()
+            .is_some_and(|layer| layer.iter().any(|&(r, c)| maze.data[r][c] == 'G'))
+        {
+            break;
+        }
+
+        let mut layer = Vec::new();
+        for _ in 0..queue.len() {
+            let cell = queue.pop_front().unwrap();
+            for (next, _) in maze.open_neighbors_dir(cell) {
+                if visited.insert(next) {
+                    layer.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        if layer.is_empty() {
+            break;
+        }
+        layers.push(layer);
+    }
+
+    layers
+}
+
+/// Writes an animated GIF with one frame per exploration layer followed by a final
+/// frame, with the solution highlighted when one is given. Returns the bytes written.
+pub fn create_gif_file(
+    maze: &Maze,
+    frames: &[Vec<(usize, usize)>],
+    solution: Option<&[(usize, usize)]>,
+    options: &GifOptions,
+    filename: &str,
+    retry: &RetryPolicy,
+) -> Result<Vec<u8>, MazeError> {
+    let scale = options.scale.max(1);
+    let mut canvas = RgbaImage::new(maze.cols as u32 * scale, maze.rows as u32 * scale);
+    for (row, line) in maze.data.iter().enumerate() {
+        for (col, &cell) in line.iter().enumerate() {
+            let color = if cell == '#' { WALL } else { FLOOR };
+            paint(&mut canvas, row, col, scale, color);
+        }
+    }
+
+    let delay = Delay::from_numer_denom_ms(options.frame_delay_ms, 1);
+    let mut bytes = Vec::new();
+    let mut encoder = GifEncoder::new(&mut bytes);
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    for layer in frames {
+        for &(row, col) in layer {
+            paint(&mut canvas, row, col, scale, VISITED);
+        }
+        paint_markers(&mut canvas, maze, scale);
+        encoder.encode_frame(Frame::from_parts(canvas.clone(), 0, 0, delay))?;
+    }
+
+    for &(row, col) in solution.unwrap_or_default() {
+        paint(&mut canvas, row, col, scale, SOLUTION);
+    }
+    paint_markers(&mut canvas, maze, scale);
+    encoder.encode_frame(Frame::from_parts(canvas, 0, 0, delay))?;
+    drop(encoder);
+
+    write_file(filename, &bytes, retry)?;
+    Ok(bytes)
+}
+
+fn paint_markers(canvas: &mut RgbaImage, maze: &Maze, scale: u32) {
+    for (row, line) in maze.data.iter().enumerate() {
+        for (col, &cell) in line.iter().enumerate() {
+            match cell {
+                'S' => paint(canvas, row, col, scale, START),
+                'G' => paint(canvas, row, col, scale, GOAL),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn paint(canvas: &mut RgbaImage, row: usize, col: usize, scale: u32, color: Rgba<u8>) {
+    let (x0, y0) = (col as u32 * scale, row as u32 * scale);
+    for y in y0..y0 + scale {
+        for x in x0..x0 + scale {
+            canvas.put_pixel(x, y, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use image::{codecs::gif::GifDecoder, AnimationDecoder};
+
+    use super::*;
+    use crate::{
+        read_maze, solve_maze,
+        test_support::{no_retry, temp_path},
+    };
+
+    #[test]
+    fn gif_has_one_frame_per_layer_plus_the_solution() {
+        let maze = read_maze("#######\n#S....#\n#.###.#\n#....G#\n#######\n".as_bytes()).unwrap();
+        let layers = bfs_layers(&maze);
+        let solution = solve_maze(&maze).unwrap();
+        let filename = &temp_path("animation.gif");
+        let options = GifOptions {
+            frame_delay_ms: 50,
+            scale: 2,
+        };
+        let retry = no_retry();
+        create_gif_file(&maze, &layers, Some(&solution), &options, filename, &retry).unwrap();
+
+        let decoder = GifDecoder::new(BufReader::new(File::open(filename).unwrap())).unwrap();
+        let frames = decoder.into_frames().collect_frames().unwrap();
+        std::fs::remove_file(filename).unwrap();
+        assert_eq!(layers.len(), 7);
+        assert_eq!(frames.len(), layers.len() + 1);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{error::MazeError, Maze};
+
+/// Whether `line` opens a maze in the knossos box style (`+---+---+`) rather than the
+/// `GameMap` style.
+pub fn is_box_style(line: &str) -> bool {
+    line.starts_with("+-") || line == "+"
+}
+
+/// Converts box-style ASCII lines into the one-character-per-cell grid. Each `+` corner,
+/// `---` segment and `|` becomes one cell, and each three-space room becomes one room cell.
+/// A room whose middle character is `S` or `G` keeps that marker; otherwise the start
+/// goes in the top-left room and the goal in the bottom-right one. Blank lines after the
+/// maze, e.g. from saving the output with an extra newline, are ignored.
+pub fn parse_box_style(lines: &[String]) -> Result<Maze, MazeError> {
+    let blank = lines
+        .iter()
+        .rev()
+        .take_while(|line| line.trim().is_empty())
+        .count();
+    let lines = &lines[..lines.len() - blank];
+    let width = lines.first().map_or(0, |line| line.chars().count()) / 4;
+    if width == 0 || lines.len() < 3 || lines.len().is_multiple_of(2) {
+        return Err(MazeError::InvalidBoxAscii { row: lines.len() });
+    }
+
+    let cols = 2 * width + 1;
+    let mut data = Vec::with_capacity(lines.len());
+    for (row, line) in lines.iter().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.len() != 4 * width + 1 {
+            return Err(MazeError::InvalidBoxAscii { row });
+        }
+        data.push(
+            (0..cols)
+                .map(|col| match chars[2 * col] {
+                    ' ' => '.',
+                    marker @ ('S' | 'G') if row % 2 == 1 && col % 2 == 1 => marker,
+                    _ => '#',
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    let rows = data.len();
+    let has = |data: &[Vec<char>], marker| data.iter().flatten().any(|&cell| cell == marker);
+    if !has(&data, 'S') {
+        data[1][1] = 'S';
+    }
+    if !has(&data, 'G') {
+        data[rows - 2][cols - 2] = 'G';
+    }
+
+    Ok(Maze { rows, cols, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use knossos::maze::AsciiBroad;
+
+    use super::*;
+    use crate::{
+        algorithm::GenerationAlgorithm, generate_maze, read_maze, render_maze, solve_maze, SIZE,
+    };
+
+    #[test]
+    fn box_style_mazes_are_detected_and_solved() {
+        let text = "\
++---+---+---+
+|       |   |
++---+   +   +
+|           |
++---+---+---+
+";
+        assert!(is_box_style(text.lines().next().unwrap()));
+        let maze = read_maze(text.as_bytes()).unwrap();
+        assert_eq!(
+            maze.to_string(),
+            "#######\n#S..#.#\n###.#.#\n#....G#\n#######\n"
+        );
+        assert_eq!(
+            solve_maze(&maze).unwrap(),
+            [(1, 1), (1, 2), (1, 3), (2, 3), (3, 3), (3, 4), (3, 5)]
+        );
+    }
+
+    #[test]
+    fn knossos_box_output_with_trailing_blank_lines_matches_the_game_map() {
+        let generated = generate_maze(GenerationAlgorithm::GrowingTree, SIZE, SIZE, 155);
+        let text = generated.format(AsciiBroad).into_inner() + "\n  \n";
+        let boxed = read_maze(text.as_bytes()).unwrap();
+        let rendered = render_maze(&generated, 155).unwrap();
+
+        assert_eq!((boxed.rows, boxed.cols), (rendered.rows, rendered.cols));
+        // The game map opens its exits in the border, so compare the interior walls.
+        for row in 1..boxed.rows - 1 {
+            for col in 1..boxed.cols - 1 {
+                assert_eq!(
+                    boxed.data[row][col] == '#',
+                    rendered.data[row][col] == '#',
+                    "cell ({}, {})",
+                    row,
+                    col
+                );
+            }
+        }
+        assert!(solve_maze(&boxed).is_some());
+    }
+
+    #[test]
+    fn ragged_box_lines_are_rejected() {
+        let lines: Vec<String> = ["+---+", "|   ", "+---+"].map(String::from).to_vec();
+        assert!(matches!(
+            parse_box_style(&lines),
+            Err(MazeError::InvalidBoxAscii { row: 1 })
+        ));
+    }
+}
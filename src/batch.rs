@@ -0,0 +1,102 @@
+use std::{collections::HashSet, fs};
+
+use crate::{error::MazeError, Maze};
+
+pub struct UniqueBatch {
+    pub seeds: Vec<u64>,
+    pub collisions: usize,
+}
+
+/// Draws consecutive seeds starting at `base_seed` until `count` mazes with distinct
+/// content hashes are found, giving up after `max_attempts` draws.
+pub fn unique_seeds<F>(
+    base_seed: u64,
+    count: usize,
+    max_attempts: usize,
+    generate: F,
+) -> Result<UniqueBatch, MazeError>
+where
+    F: Fn(u64) -> Result<Maze, MazeError>,
+{
+    let mut hashes = HashSet::with_capacity(count);
+    let mut seeds = Vec::with_capacity(count);
+    let mut collisions = 0;
+
+    for attempt in 0..max_attempts as u64 {
+        if seeds.len() == count {
+            break;
+        }
+        let seed = base_seed.wrapping_add(attempt);
+        let maze = generate(seed)?;
+        if hashes.insert(maze.content_hash()) {
+            seeds.push(seed);
+        } else {
+            collisions += 1;
+        }
+    }
+
+    if seeds.len() < count {
+        return Err(MazeError::AttemptsExhausted {
+            requested: count,
+            found: seeds.len(),
+            attempts: max_attempts,
+        });
+    }
+
+    Ok(UniqueBatch { seeds, collisions })
+}
+
+/// Reads one seed per line, e.g. from a manifest, to replay a batch exactly. Blank lines
+/// are skipped.
+pub fn read_seed_file(filename: &str) -> Result<Vec<u64>, MazeError> {
+    fs::read_to_string(filename)?
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(line, text)| text.parse().map_err(|_| MazeError::InvalidSeed { line }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use knossos::maze::*;
+
+    use super::*;
+    use crate::{game_map, read_maze};
+
+    /// A 2x2 maze has only a handful of layouts, so consecutive seeds collide often.
+    fn tiny(seed: u64) -> Result<Maze, MazeError> {
+        let text = OrthogonalMazeBuilder::new()
+            .height(2)
+            .width(2)
+            .seed(Some(seed))
+            .build()
+            .format(game_map(seed))
+            .into_inner();
+        read_maze(text.as_bytes())
+    }
+
+    #[test]
+    fn tiny_batch_reaches_the_count_or_errors_at_the_cap() {
+        let batch = unique_seeds(0, 4, 100, tiny).unwrap();
+        assert_eq!(batch.seeds.len(), 4);
+        // Every draw before the last distinct maze that was not kept was a collision.
+        assert_eq!(batch.collisions as u64, batch.seeds[3] + 1 - 4);
+        let hashes: HashSet<_> = batch
+            .seeds
+            .iter()
+            .map(|&seed| tiny(seed).unwrap().content_hash())
+            .collect();
+        assert_eq!(hashes.len(), 4);
+
+        match unique_seeds(0, 1000, 100, tiny) {
+            Err(MazeError::AttemptsExhausted {
+                requested: 1000,
+                found,
+                attempts: 100,
+            }) => assert!(found < 100),
+            _ => panic!("expected the attempt cap to be hit"),
+        }
+    }
+}
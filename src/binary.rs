@@ -0,0 +1,137 @@
+use std::fs;
+
+use crate::{
+    error::MazeError,
+    retry::{write_file, RetryPolicy},
+    Maze,
+};
+
+/// First four bytes of every binary maze file.
+pub const MAGIC: [u8; 4] = *b"LBYR";
+/// Format version written by [`create_binary_file`]; readers reject any other.
+pub const VERSION: u8 = 1;
+
+const LITTLE_ENDIAN: u8 = b'L';
+const BIG_ENDIAN: u8 = b'B';
+const HEADER_LEN: usize = 14;
+
+/// Header: magic, version byte, byte-order byte (`L` or `B`), then width and height as
+/// `u32` in that byte order. The body is one ASCII cell character per cell, row by row.
+/// Files are always written little-endian, but big-endian files are read too. Returns the
+/// bytes written.
+pub fn create_binary_file(
+    maze: &Maze,
+    filename: &str,
+    retry: &RetryPolicy,
+) -> Result<Vec<u8>, MazeError> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + maze.rows * maze.cols);
+    bytes.extend_from_slice(&MAGIC);
+    bytes.push(VERSION);
+    bytes.push(LITTLE_ENDIAN);
+    bytes.extend_from_slice(&(maze.cols as u32).to_le_bytes());
+    bytes.extend_from_slice(&(maze.rows as u32).to_le_bytes());
+    for (row, line) in maze.data.iter().enumerate() {
+        for (col, &cell) in line.iter().enumerate() {
+            match u8::try_from(cell) {
+                Ok(byte) if byte.is_ascii() => bytes.push(byte),
+                _ => return Err(MazeError::UnencodableCell { row, col, cell }),
+            }
+        }
+    }
+    write_file(filename, &bytes, retry)?;
+    Ok(bytes)
+}
+
+pub fn read_maze_binary(filename: &str) -> Result<Maze, MazeError> {
+    parse_binary(&fs::read(filename)?)
+}
+
+pub fn parse_binary(bytes: &[u8]) -> Result<Maze, MazeError> {
+    if bytes.len() < HEADER_LEN || bytes[..4] != MAGIC {
+        return Err(MazeError::InvalidBinary);
+    }
+    if bytes[4] != VERSION {
+        return Err(MazeError::UnsupportedBinaryVersion { version: bytes[4] });
+    }
+    let read_u32 = |offset: usize| {
+        let word: [u8; 4] = bytes[offset..offset + 4].try_into().unwrap();
+        match bytes[5] {
+            LITTLE_ENDIAN => Ok(u32::from_le_bytes(word) as usize),
+            BIG_ENDIAN => Ok(u32::from_be_bytes(word) as usize),
+            _ => Err(MazeError::InvalidBinary),
+        }
+    };
+    let cols = read_u32(6)?;
+    let rows = read_u32(10)?;
+
+    let body = &bytes[HEADER_LEN..];
+    if cols.checked_mul(rows) != Some(body.len()) || !body.is_ascii() {
+        return Err(MazeError::InvalidBinary);
+    }
+    let data = if cols == 0 {
+        vec![Vec::new(); rows]
+    } else {
+        body.chunks(cols)
+            .map(|line| line.iter().map(|&byte| byte as char).collect())
+            .collect()
+    };
+
+    Ok(Maze { rows, cols, data })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{
+        read_maze,
+        test_support::{no_retry, temp_path},
+    };
+
+    fn written(maze: &Maze) -> Vec<u8> {
+        let filename = &temp_path("binary.lbyr");
+        let retry = no_retry();
+        create_binary_file(maze, filename, &retry).unwrap();
+        let bytes = fs::read(filename).unwrap();
+        fs::remove_file(filename).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn a_bumped_version_byte_is_rejected() {
+        let maze = read_maze("#####\n#S.G#\n#####\n".as_bytes()).unwrap();
+        let mut bytes = written(&maze);
+        assert_eq!(parse_binary(&bytes).unwrap().data, maze.data);
+
+        bytes[4] = VERSION + 1;
+        assert!(matches!(
+            parse_binary(&bytes),
+            Err(MazeError::UnsupportedBinaryVersion { version }) if version == VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn big_endian_headers_are_read() {
+        let maze = read_maze("#####\n#S.G#\n#####\n".as_bytes()).unwrap();
+        let mut bytes = written(&maze);
+        bytes[5] = BIG_ENDIAN;
+        bytes[6..10].copy_from_slice(&5u32.to_be_bytes());
+        bytes[10..14].copy_from_slice(&3u32.to_be_bytes());
+        assert_eq!(parse_binary(&bytes).unwrap().data, maze.data);
+    }
+
+    #[test]
+    fn cells_that_are_not_one_ascii_byte_are_rejected() {
+        let filename = &temp_path("binary-wide.lbyr");
+        let retry = no_retry();
+        for wide in ["#####\n#S\u{2588}G#\n#####\n", "#####\n#S\u{e9}G#\n#####\n"] {
+            let maze = read_maze(wide.as_bytes()).unwrap();
+            let cell = maze.data[1][2];
+            assert!(matches!(
+                create_binary_file(&maze, filename, &retry),
+                Err(MazeError::UnencodableCell { row: 1, col: 2, cell: c }) if c == cell
+            ));
+        }
+        assert!(!std::path::Path::new(filename).exists());
+    }
+}
@@ -0,0 +1,71 @@
+use std::{io, path::Path};
+
+use sha2::{Digest, Sha256};
+
+use crate::retry::{write_file, RetryPolicy};
+
+/// Lowercase hex SHA-256 digest of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Writes `<filename>.sha256` with the hash of `bytes`, the contents just written to
+/// `filename`, in the `sha256sum` format so `sha256sum -c` can check it from the same
+/// directory.
+pub fn write_checksum(filename: &str, bytes: &[u8], retry: &RetryPolicy) -> io::Result<()> {
+    let hash = sha256_hex(bytes);
+    let name = Path::new(filename)
+        .file_name()
+        .map_or(filename.into(), |name| name.to_string_lossy());
+    write_file(
+        &format!("{}.sha256", filename),
+        format!("{}  {}\n", hash, name).as_bytes(),
+        retry,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{
+        create_json_file, solve_maze,
+        test_support::{generated, no_retry, temp_path},
+        JsonOptions,
+    };
+
+    #[test]
+    fn sha256_matches_a_known_digest() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn the_sidecar_holds_the_hash_of_the_written_json() {
+        let maze = generated(156);
+        let solution = solve_maze(&maze).unwrap();
+        let filename = &temp_path("checksum.json");
+        let retry = no_retry();
+        let options = JsonOptions::default();
+        let bytes = create_json_file(
+            maze.cols, maze.rows, &maze, &solution, filename, &options, &retry,
+        )
+        .unwrap();
+        write_checksum(filename, &bytes, &retry).unwrap();
+
+        let sidecar = format!("{}.sha256", filename);
+        let line = fs::read_to_string(&sidecar).unwrap();
+        let expected = sha256_hex(&fs::read(filename).unwrap());
+        fs::remove_file(filename).unwrap();
+        fs::remove_file(&sidecar).unwrap();
+        assert_eq!(sha256_hex(&bytes), expected);
+        let name = Path::new(filename).file_name().unwrap().to_str().unwrap();
+        assert_eq!(line, format!("{}  {}\n", expected, name));
+    }
+}
@@ -0,0 +1,448 @@
+use std::time::Duration;
+
+use clap::{Parser, ValueEnum};
+
+use labyrinthium_generator::{
+    algorithm::{AlgorithmMix, GenerationAlgorithm},
+    render::RenderStyle,
+    retry::RetryPolicy,
+    solver::{DirectionOrder, GoalSelection},
+    template::OutputTemplate,
+    transform::GoalPlacement,
+    JsonOptions, Layout, ReadMode, SIZE,
+};
+
+/// Generate, solve and export orthogonal mazes.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Args {
+    /// Compare two maze files cell by cell instead of generating a maze
+    #[arg(long, num_args = 2, value_names = ["A", "B"])]
+    pub diff: Option<Vec<String>>,
+
+    /// Number of mazes to generate
+    #[arg(long, env = "LAB_COUNT", default_value_t = 1)]
+    pub count: usize,
+
+    /// Seed for the first maze; each further maze in a batch uses the next seed
+    #[arg(long, env = "LAB_SEED")]
+    pub seed: Option<u64>,
+
+    /// Maze width in rooms; the grid is 2 * WIDTH + 1 cells wide
+    #[arg(long, env = "LAB_WIDTH", default_value_t = SIZE, value_parser = parse_rooms)]
+    pub width: usize,
+
+    /// Maze height in rooms; the grid is 2 * HEIGHT + 1 cells tall
+    #[arg(long, env = "LAB_HEIGHT", default_value_t = SIZE, value_parser = parse_rooms)]
+    pub height: usize,
+
+    /// Generate one maze per seed listed in this file, one per line, instead of --count
+    #[arg(long, value_name = "FILE", conflicts_with = "repeat_until_unique")]
+    pub seed_file: Option<String>,
+
+    /// Weighted generation algorithms to draw from per maze, e.g. backtracking=0.5,prim=0.5
+    /// [default: growing-tree]
+    #[arg(long, value_name = "MIX")]
+    pub algorithm_mix: Option<AlgorithmMix>,
+
+    /// Regenerate a maze whose solution has fewer cells than this
+    #[arg(long, value_name = "N")]
+    pub min_path: Option<usize>,
+
+    /// Regenerate a maze whose solution has more cells than this
+    #[arg(long, value_name = "N")]
+    pub max_path: Option<usize>,
+
+    /// Give up on --min-path/--max-path after this many seeds per maze
+    #[arg(long, value_name = "N", default_value_t = 100)]
+    pub path_attempts: usize,
+
+    /// Keep drawing new seeds until the batch contains `count` distinct mazes
+    #[arg(long)]
+    pub repeat_until_unique: bool,
+
+    /// Give up on --repeat-until-unique after this many seeds [default: 10 * count]
+    #[arg(long, value_name = "N")]
+    pub max_attempts: Option<usize>,
+
+    /// Derive every maze in the batch from the base seed's maze, keeping the cells within
+    /// RADIUS of the start and re-randomizing the rest with each maze's own seed
+    #[arg(long, value_name = "RADIUS")]
+    pub ensemble: Option<f64>,
+
+    /// Carve the maze only inside the light pixels of this black-and-white image
+    #[arg(long, value_name = "FILE")]
+    pub mask: Option<String>,
+
+    /// Read maze files leniently, treating unrecognised characters as floor
+    #[arg(long)]
+    pub lenient: bool,
+
+    /// Warn when less than this fraction of the grid is open
+    #[arg(long, default_value_t = 0.25, value_parser = parse_ratio)]
+    pub min_open_density: f64,
+
+    /// Warn when more than this fraction of the grid is open
+    #[arg(long, default_value_t = 0.75, value_parser = parse_ratio)]
+    pub max_open_density: f64,
+
+    /// Carve an interior wall next to a start or goal that has no open neighbour, failing
+    /// the maze when no such wall joins it to an open cell
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Carve with the built-in recursive backtracker and store its steps in build_steps
+    #[arg(long, conflicts_with = "ensemble")]
+    pub record_build: bool,
+
+    /// Also write the generated maze as text to maze.txt before any transforms
+    #[arg(long)]
+    pub emit_txt: bool,
+
+    /// How to print the maze: plain, unicode or ansi
+    #[arg(long, value_name = "STYLE", default_value = "plain")]
+    pub render: RenderStyle,
+
+    /// Draw only ASCII glyphs, printing unicode as plain, and fail on any other character
+    #[arg(long)]
+    pub ascii_only: bool,
+
+    /// Print the maze with row and column index rulers (mod 10) along the margins
+    #[arg(long)]
+    pub pretty_ascii: bool,
+
+    /// Draw the solution over the printed maze
+    #[arg(long)]
+    pub show_solution: bool,
+
+    /// Generate and solve the maze, print its metrics and write no files
+    #[arg(
+        long,
+        conflicts_with_all = ["emit_txt", "gif", "manifest", "profile", "bundle", "summary_json", "checksum"]
+    )]
+    pub stats_only: bool,
+
+    /// Open this fraction (0.0 to 1.0) of the interior walls between corridors
+    #[arg(long, value_name = "RATIO", value_parser = parse_ratio)]
+    pub openness: Option<f64>,
+
+    /// Add exactly this many loops to the generated perfect maze
+    #[arg(long, value_name = "N")]
+    pub loops: Option<usize>,
+
+    /// Centre the maze on a wall-filled canvas of this size, e.g. 41x41
+    #[arg(long, value_name = "WxH", value_parser = parse_dimensions)]
+    pub pad_to: Option<(usize, usize)>,
+
+    /// Blow every cell up into a FACTOR x FACTOR block, widening corridors and walls
+    #[arg(long, value_name = "FACTOR", value_parser = clap::value_parser!(u64).range(1..))]
+    pub upscale: Option<u64>,
+
+    /// Open walls or fill dead ends until the maze has about this many open cells. The
+    /// grid is never grown: at most the (2 * WIDTH - 1) x (2 * HEIGHT - 1) interior and
+    /// any border exits can open, so raise --width/--height for larger counts
+    #[arg(long, value_name = "K")]
+    pub open_cells: Option<usize>,
+
+    /// How far the open-cell count may end up from --open-cells
+    #[arg(long, value_name = "N", default_value_t = 5)]
+    pub open_cells_tolerance: usize,
+
+    /// Braid away dead ends or add spurs until the maze has about this many
+    #[arg(long, value_name = "N")]
+    pub dead_ends: Option<usize>,
+
+    /// Open walls until sampled pairs of cells have K vertex-disjoint paths between them,
+    /// an approximation of K-connectivity (1 leaves a perfect maze as it is)
+    #[arg(long, value_name = "K", value_parser = clap::value_parser!(u32).range(1..=4))]
+    pub connectivity: Option<u32>,
+
+    /// Swap walls and open cells, moving the start and goal onto the nearest open cells
+    #[arg(long)]
+    pub invert: bool,
+
+    /// Move the goal to the reachable open cell nearest top-left, top-right, bottom-left,
+    /// bottom-right or center, or to a seeded random one with random
+    #[arg(long, value_name = "PLACEMENT")]
+    pub goal_placement: Option<GoalPlacement>,
+
+    /// Give floor cells random weights from 1 to MAX (at most 9), written as digits
+    #[arg(long, value_name = "MAX", value_parser = clap::value_parser!(u32).range(1..=9))]
+    pub terrain: Option<u32>,
+
+    /// Solve with Dijkstra over the cell weights instead of DFS
+    #[arg(long)]
+    pub weighted: bool,
+
+    /// Solve with BFS toward the `nearest`, `farthest` or N-th goal when there are several
+    #[arg(long, value_name = "SELECTION")]
+    pub goal_selection: Option<GoalSelection>,
+
+    /// Neighbour order for the default DFS solver, most preferred first, e.g. rdlu
+    /// [default: rldu]
+    #[arg(long, value_name = "ORDER")]
+    pub dfs_order: Option<DirectionOrder>,
+
+    /// Run every solver on the maze and print their path lengths and expanded cells
+    #[arg(long)]
+    pub compare_solvers: bool,
+
+    /// Output file format
+    #[arg(long, env = "LAB_FORMAT", value_enum, default_value_t = OutputFormat::Json)]
+    pub format: OutputFormat,
+
+    /// Directory for the maze files
+    #[arg(
+        long,
+        value_name = "DIR",
+        env = "LAB_OUT_DIR",
+        default_value = "output"
+    )]
+    pub out_dir: String,
+
+    /// Name of the main output file under --out-dir, e.g. "maze_{w}x{h}_{seed}_{i}.{ext}",
+    /// where {w} and {h} are the grid width and height and {i} the batch index
+    #[arg(long, value_name = "TEMPLATE")]
+    pub output_template: Option<OutputTemplate>,
+
+    /// Shorthand for --format rle
+    #[arg(long)]
+    pub rle: bool,
+
+    /// Shorthand for --format grid
+    #[arg(long)]
+    pub grid_json: bool,
+
+    /// Shorthand for --format solution: write only the endpoints and the path
+    #[arg(long)]
+    pub solution_only_json: bool,
+
+    /// Emit JSON cells in column-major order
+    #[arg(long)]
+    pub transpose: bool,
+
+    /// Accepted for older scripts; start, goal and solution have always been written with
+    /// x as the column and y as the row, so the legacy output is the current output
+    #[arg(long, visible_alias = "flip-coordinates")]
+    pub legacy_coords: bool,
+
+    /// Include the BFS spanning tree as child/parent pairs in the JSON
+    #[arg(long)]
+    pub tree: bool,
+
+    /// Include the corridors as polylines (endpoints and corners only) in the JSON
+    #[arg(long)]
+    pub polylines: bool,
+
+    /// Include the junction graph, weighted by corridor length, under navmesh in the JSON
+    #[arg(long)]
+    pub navmesh: bool,
+
+    /// Include the walls merged into rectangles under wall_rects in the JSON
+    #[arg(long)]
+    pub wall_rects: bool,
+
+    /// Count the distinct shortest paths (modulo 1e9+7) into stats.shortest_path_count
+    #[arg(long)]
+    pub count_solutions: bool,
+
+    /// Record the open width across the solution at every step in stats.corridor_width_profile
+    #[arg(long)]
+    pub corridor_widths: bool,
+
+    /// Add a line-of-sight smoothed copy of the solution for any-angle movement
+    #[arg(long)]
+    pub smooth: bool,
+
+    /// Also give the solution as indices into the JSON cell array
+    #[arg(long)]
+    pub solution_indices: bool,
+
+    /// Also emit every K-th solution cell, plus the goal, under solution_sampled
+    #[arg(long, value_name = "K", value_parser = clap::value_parser!(u64).range(1..))]
+    pub sample_solution: Option<u64>,
+
+    /// Tag cells with distance bands of this many steps from the start in the JSON
+    #[arg(long, value_name = "WIDTH")]
+    pub regions: Option<usize>,
+
+    /// Write an animated GIF of the breadth-first search to this file
+    #[arg(long, value_name = "FILE")]
+    pub gif: Option<String>,
+
+    /// Delay between GIF frames in milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    pub gif_delay: u32,
+
+    /// Size of one maze cell in GIF pixels
+    #[arg(long, value_name = "PX", default_value_t = 8)]
+    pub gif_scale: u32,
+
+    /// Write a JSON manifest with the file, seed, algorithm, size and hash of every maze
+    #[arg(long, value_name = "FILE")]
+    pub manifest: Option<String>,
+
+    /// Write the nanoseconds spent reading, generating, solving and serializing as JSON
+    #[arg(long, value_name = "FILE")]
+    pub profile: Option<String>,
+
+    /// Give up on a single maze after this many seconds and carry on with the batch
+    #[arg(long, value_name = "SECS")]
+    pub maze_timeout: Option<f64>,
+
+    /// Also append every maze as one JSON line to this bundle file
+    #[arg(long, value_name = "FILE")]
+    pub bundle: Option<String>,
+
+    /// Keep the existing lines of --bundle instead of starting it afresh
+    #[arg(long, requires = "bundle")]
+    pub append: bool,
+
+    /// Print min/median/mean/max of the per-maze stats after the batch
+    #[arg(long)]
+    pub summary: bool,
+
+    /// Also write the batch summary as JSON to this file
+    #[arg(long, value_name = "FILE")]
+    pub summary_json: Option<String>,
+
+    /// Write a .sha256 sidecar with the hash of every output file
+    #[arg(long)]
+    pub checksum: bool,
+
+    /// Number of attempts for each output file write before giving up
+    #[arg(long, value_name = "N", default_value_t = 3)]
+    pub write_attempts: u32,
+
+    /// Delay before the first write retry in milliseconds, doubled on each further retry
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    pub write_retry_delay: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Per-cell JSON
+    Json,
+    /// Run-length encoded rows
+    Rle,
+    /// Tiled editor JSON map
+    Tiled,
+    /// Start, goal and solution without the cells
+    Solution,
+    /// Versioned binary grid
+    Binary,
+    /// Nested row arrays of cell type codes
+    Grid,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Rle => "rle.json",
+            OutputFormat::Tiled => "tiled.json",
+            OutputFormat::Solution => "solution.json",
+            OutputFormat::Binary => "bin",
+            OutputFormat::Grid => "grid.json",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "JSON",
+            OutputFormat::Rle => "RLE",
+            OutputFormat::Tiled => "Tiled",
+            OutputFormat::Solution => "Solution",
+            OutputFormat::Binary => "Binary",
+            OutputFormat::Grid => "Grid JSON",
+        }
+    }
+}
+
+impl Args {
+    pub fn output_format(&self) -> OutputFormat {
+        if self.rle {
+            OutputFormat::Rle
+        } else if self.solution_only_json {
+            OutputFormat::Solution
+        } else if self.grid_json {
+            OutputFormat::Grid
+        } else {
+            self.format
+        }
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            attempts: self.write_attempts,
+            delay: Duration::from_millis(self.write_retry_delay),
+        }
+    }
+
+    pub fn read_mode(&self) -> ReadMode {
+        if self.lenient {
+            ReadMode::Lenient
+        } else {
+            ReadMode::Strict
+        }
+    }
+
+    pub fn algorithm(&self, seed: u64) -> GenerationAlgorithm {
+        match &self.algorithm_mix {
+            _ if self.record_build => GenerationAlgorithm::Backtracking,
+            Some(mix) => mix.pick(seed),
+            None => GenerationAlgorithm::GrowingTree,
+        }
+    }
+
+    pub fn json_options(&self, algorithm: GenerationAlgorithm) -> JsonOptions {
+        JsonOptions {
+            algorithm: Some(algorithm),
+            tree: self.tree,
+            polylines: self.polylines,
+            navmesh: self.navmesh,
+            wall_rects: self.wall_rects,
+            count_solutions: self.count_solutions,
+            corridor_widths: self.corridor_widths,
+            smooth: self.smooth,
+            region_band: self.regions,
+            solution_indices: self.solution_indices,
+            sample_every: self.sample_solution.map(|k| k as usize),
+            solver: None,
+            expanded_nodes: None,
+            build_steps: None,
+            layout: if self.transpose {
+                Layout::ColumnMajor
+            } else {
+                Layout::RowMajor
+            },
+        }
+    }
+}
+
+fn parse_ratio(s: &str) -> Result<f64, String> {
+    let ratio: f64 = s.parse().map_err(|_| format!("'{}' is not a number", s))?;
+    if (0.0..=1.0).contains(&ratio) {
+        Ok(ratio)
+    } else {
+        Err(format!("{} is not between 0.0 and 1.0", ratio))
+    }
+}
+
+fn parse_rooms(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(0) => Err("a maze needs at least one room".to_string()),
+        Ok(rooms) => Ok(rooms),
+        Err(_) => Err(format!("'{}' is not a room count", s)),
+    }
+}
+
+fn parse_dimensions(s: &str) -> Result<(usize, usize), String> {
+    let (width, height) = s
+        .split_once('x')
+        .ok_or_else(|| format!("expected WxH, got '{}'", s))?;
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height)) => Ok((width, height)),
+        _ => Err(format!("expected WxH, got '{}'", s)),
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{error::MazeError, Maze};
+
+/// Stitches same-sized tiles, in row-major order with `cols` per row, into one maze.
+/// Neighbouring tiles share their border row or column. Each tile's start and goal
+/// become floor and its outer entrances are walled up. Every pair of horizontal
+/// neighbours and the tiles of the first column are then joined by one opening, so a
+/// set of perfect tiles gives a perfect composite. The start goes in the top-left room
+/// and the goal in the bottom-right one.
+pub fn composite(tiles: &[Maze], cols: usize) -> Result<Maze, MazeError> {
+    let Some(first) = tiles.first() else {
+        return Err(MazeError::IncompleteTileGrid { tiles: 0, cols });
+    };
+    if cols == 0 || !tiles.len().is_multiple_of(cols) {
+        return Err(MazeError::IncompleteTileGrid {
+            tiles: tiles.len(),
+            cols,
+        });
+    }
+    if let Some(tile) = tiles
+        .iter()
+        .find(|tile| (tile.rows, tile.cols) != (first.rows, first.cols))
+    {
+        return Err(MazeError::DimensionMismatch {
+            left: (first.rows, first.cols),
+            right: (tile.rows, tile.cols),
+        });
+    }
+
+    let (tile_rows, tile_cols) = (first.rows, first.cols);
+    // Seams need a wall on each side and the start a room inside the border.
+    if tile_rows < 3 || tile_cols < 3 {
+        return Err(MazeError::TileTooSmall {
+            rows: tile_rows,
+            cols: tile_cols,
+        });
+    }
+    let grid_rows = tiles.len() / cols;
+    let rows = grid_rows * (tile_rows - 1) + 1;
+    let width = cols * (tile_cols - 1) + 1;
+    let mut data = vec![vec!['#'; width]; rows];
+
+    for (index, tile) in tiles.iter().enumerate() {
+        let (top, left) = (
+            index / cols * (tile_rows - 1),
+            index % cols * (tile_cols - 1),
+        );
+        for row in 1..tile_rows - 1 {
+            for col in 1..tile_cols - 1 {
+                data[top + row][left + col] = match tile.data[row][col] {
+                    'S' | 'G' => '.',
+                    cell => cell,
+                };
+            }
+        }
+    }
+
+    for tile_row in 0..grid_rows {
+        let top = tile_row * (tile_rows - 1);
+        for tile_col in 1..cols {
+            let seam = tile_col * (tile_cols - 1);
+            let opening = seam_opening(tile_rows, |offset| {
+                let row = top + offset;
+                data[row][seam - 1] != '#' && data[row][seam + 1] != '#'
+            })
+            .ok_or(MazeError::NoSeamOpening {
+                row: top,
+                col: seam,
+            })?;
+            data[top + opening][seam] = '.';
+        }
+        if tile_row > 0 {
+            let opening = seam_opening(tile_cols, |col| {
+                data[top - 1][col] != '#' && data[top + 1][col] != '#'
+            })
+            .ok_or(MazeError::NoSeamOpening { row: top, col: 0 })?;
+            data[top][opening] = '.';
+        }
+    }
+
+    data[1][1] = 'S';
+    data[rows - 2][width - 2] = 'G';
+    Ok(Maze {
+        rows,
+        cols: width,
+        data,
+    })
+}
+
+/// Offset along a seam of `length` cells, nearest its middle, where both sides are open.
+fn seam_opening<F: Fn(usize) -> bool>(length: usize, open: F) -> Option<usize> {
+    let middle = length / 2;
+    (1..length - 1)
+        .filter(|&offset| open(offset))
+        .min_by_key(|&offset| offset.abs_diff(middle))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_start, solve_maze, solver::bfs_tree, test_support::generated};
+
+    fn tiles(count: u64) -> Vec<Maze> {
+        (0..count).map(generated).collect()
+    }
+
+    #[test]
+    fn four_tiles_stitch_into_one_connected_maze() {
+        let maze = composite(&tiles(4), 2).unwrap();
+        assert_eq!((maze.rows, maze.cols), (41, 41));
+        assert!(solve_maze(&maze).is_some());
+        let reachable = bfs_tree(&maze, find_start(&maze)).distances.len();
+        assert_eq!(reachable, maze.open_cell_count());
+    }
+
+    #[test]
+    fn an_incomplete_tile_grid_is_rejected() {
+        assert!(matches!(
+            composite(&tiles(3), 2),
+            Err(MazeError::IncompleteTileGrid { tiles: 3, cols: 2 })
+        ));
+    }
+}
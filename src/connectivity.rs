@@ -0,0 +1,230 @@
+use std::collections::{HashMap, VecDeque};
+
+use rand::{seq::IndexedRandom, Rng};
+
+use crate::{
+    error::MazeError,
+    solver::{shortest_path_between, Solution},
+    Maze,
+};
+
+type Point = (usize, usize);
+
+/// Random pairs of cells checked per round of [`raise_connectivity`].
+const SAMPLED_PAIRS: usize = 64;
+
+/// Number of paths between `from` and `to` that share no cell other than their ends,
+/// stopping once `limit` are found. Only open cells off the outer border are used.
+pub fn vertex_disjoint_paths(maze: &Maze, from: Point, to: Point, limit: usize) -> usize {
+    // Every cell is split into an entry node 2i and an exit node 2i + 1 joined by an arc
+    // of capacity 1, so a unit max-flow counts vertex-disjoint paths.
+    let index = |(row, col): Point| row * maze.cols + col;
+    let mut capacity: HashMap<(usize, usize), u8> = HashMap::new();
+    let mut arcs: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut add_arc = |a: usize, b: usize| {
+        capacity.insert((a, b), 1);
+        capacity.entry((b, a)).or_insert(0);
+        arcs.entry(a).or_default().push(b);
+        arcs.entry(b).or_default().push(a);
+    };
+    for cell in interior_open_cells(maze) {
+        add_arc(2 * index(cell), 2 * index(cell) + 1);
+        for next in interior_neighbors(maze, cell) {
+            add_arc(2 * index(cell) + 1, 2 * index(next));
+        }
+    }
+
+    let (source, sink) = (2 * index(from) + 1, 2 * index(to));
+    let mut paths = 0;
+    while paths < limit {
+        let mut parents = HashMap::from([(source, source)]);
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            if node == sink {
+                break;
+            }
+            for &next in arcs.get(&node).into_iter().flatten() {
+                if capacity[&(node, next)] > 0 && !parents.contains_key(&next) {
+                    parents.insert(next, node);
+                    queue.push_back(next);
+                }
+            }
+        }
+        if !parents.contains_key(&sink) {
+            break;
+        }
+        let mut node = sink;
+        while node != source {
+            let parent = parents[&node];
+            *capacity.get_mut(&(parent, node)).unwrap() -= 1;
+            *capacity.get_mut(&(node, parent)).unwrap() += 1;
+            node = parent;
+        }
+        paths += 1;
+    }
+    paths
+}
+
+/// Opens interior walls until a sampled check finds every pair of open cells joined by
+/// `k` vertex-disjoint paths, and returns how many walls were opened. This approximates
+/// k-connectivity: each round checks a few dozen random pairs rather than all of them.
+/// The outer border is never opened, so border cells such as an edge start or goal are
+/// left out, and a pair only needs as many paths as its cells can have neighbours off
+/// the border (two in a corner). A `k` of 1 or less asks for nothing beyond a connected
+/// maze.
+pub fn raise_connectivity<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    k: usize,
+    rng: &mut R,
+) -> Result<usize, MazeError> {
+    let budget = interior_cells(maze).len();
+    let mut opened = 0;
+    let potential = |maze: &Maze, cell: Point| {
+        neighbors(maze, cell)
+            .into_iter()
+            .filter(|&next| is_interior(maze, next))
+            .count()
+            .min(k)
+    };
+
+    while opened <= budget {
+        let cells = interior_open_cells(maze);
+        // A cell with fewer open neighbours than it needs can never pass, so widen it first.
+        let starved = cells
+            .iter()
+            .copied()
+            .find(|&cell| interior_neighbors(maze, cell).len() < potential(maze, cell));
+        let failing = match starved {
+            Some(cell) => Some(vec![cell]),
+            None => (0..SAMPLED_PAIRS).find_map(|_| {
+                let pair: Vec<Point> = cells.choose_multiple(rng, 2).copied().collect();
+                let &[from, to] = pair.as_slice() else {
+                    return None;
+                };
+                let needed = potential(maze, from).min(potential(maze, to));
+                if vertex_disjoint_paths(maze, from, to, needed) < needed {
+                    shortest_path_between(maze, from, to)
+                        .map(Solution::into_path)
+                        .or(Some(pair))
+                } else {
+                    None
+                }
+            }),
+        };
+        let Some(route) = failing else {
+            return Ok(opened);
+        };
+
+        // Open a wall next to the failing cells that joins at least two open cells, or
+        // any wall next to them when none does.
+        let walls: Vec<Point> = route
+            .iter()
+            .flat_map(|&cell| neighbors(maze, cell))
+            .filter(|&wall| is_interior(maze, wall) && maze.data[wall.0][wall.1] == '#')
+            .collect();
+        let joining: Vec<Point> = walls
+            .iter()
+            .copied()
+            .filter(|&wall| interior_neighbors(maze, wall).len() >= 2)
+            .collect();
+        match joining.choose(rng).or_else(|| walls.choose(rng)) {
+            Some(&(row, col)) => maze.data[row][col] = '.',
+            None => break,
+        }
+        opened += 1;
+    }
+    Err(MazeError::ConnectivityUnmet { k, opened })
+}
+
+fn is_interior(maze: &Maze, (row, col): Point) -> bool {
+    row > 0 && col > 0 && row + 1 < maze.rows && col + 1 < maze.cols
+}
+
+fn interior_cells(maze: &Maze) -> Vec<Point> {
+    (0..maze.rows)
+        .flat_map(|row| (0..maze.cols).map(move |col| (row, col)))
+        .filter(|&cell| is_interior(maze, cell))
+        .collect()
+}
+
+fn interior_open_cells(maze: &Maze) -> Vec<Point> {
+    interior_cells(maze)
+        .into_iter()
+        .filter(|&(row, col)| maze.data[row][col] != '#')
+        .collect()
+}
+
+/// Orthogonal neighbours of `cell` inside the grid, open or not.
+fn neighbors(maze: &Maze, (row, col): Point) -> Vec<Point> {
+    let mut cells = Vec::with_capacity(4);
+    if row > 0 {
+        cells.push((row - 1, col));
+    }
+    if row + 1 < maze.rows {
+        cells.push((row + 1, col));
+    }
+    if col > 0 {
+        cells.push((row, col - 1));
+    }
+    if col + 1 < maze.cols {
+        cells.push((row, col + 1));
+    }
+    cells
+}
+
+fn interior_neighbors(maze: &Maze, cell: Point) -> Vec<Point> {
+    maze.open_neighbors_dir(cell)
+        .into_iter()
+        .map(|(next, _)| next)
+        .filter(|&next| is_interior(maze, next))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        find_goals, find_start,
+        solver::{solve_avoiding, Bfs, PathFinder},
+        test_support::generated,
+    };
+
+    #[test]
+    fn two_connected_mazes_have_no_cut_vertex_on_the_route() {
+        let mut maze = generated(187);
+        let opened = raise_connectivity(&mut maze, 2, &mut StdRng::seed_from_u64(187)).unwrap();
+        assert!(opened > 0);
+
+        // The start and goal sit on the border with one way in, so the cells next to them
+        // are cut vertices by construction; every other cell of the route must have a bypass.
+        let (start, goal) = (find_start(&maze), find_goals(&maze)[0]);
+        let entrances: HashSet<_> = [start, goal]
+            .into_iter()
+            .flat_map(|cell| maze.open_neighbors_dir(cell))
+            .map(|(next, _)| next)
+            .collect();
+        let route = Bfs.find_path(&maze).unwrap().into_path();
+        for &cell in &route[1..route.len() - 1] {
+            if !entrances.contains(&cell) {
+                assert!(
+                    solve_avoiding(&maze, &HashSet::from([cell])).is_some(),
+                    "{:?} is a cut vertex",
+                    cell
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn k_of_one_leaves_a_perfect_maze_alone() {
+        let mut maze = generated(187);
+        assert_eq!(
+            raise_connectivity(&mut maze, 1, &mut StdRng::seed_from_u64(187)).unwrap(),
+            0
+        );
+    }
+}
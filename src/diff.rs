@@ -0,0 +1,64 @@
+use crate::{error::MazeError, Maze};
+
+pub struct CellDiff {
+    pub row: usize,
+    pub col: usize,
+    pub left: char,
+    pub right: char,
+}
+
+/// Lists every cell whose character differs between two mazes of equal dimensions.
+pub fn diff_mazes(left: &Maze, right: &Maze) -> Result<Vec<CellDiff>, MazeError> {
+    if left.rows != right.rows || left.cols != right.cols {
+        return Err(MazeError::DimensionMismatch {
+            left: (left.cols, left.rows),
+            right: (right.cols, right.rows),
+        });
+    }
+
+    let mut diffs = Vec::new();
+    for (row, (left_line, right_line)) in left.data.iter().zip(&right.data).enumerate() {
+        for (col, (&l, &r)) in left_line.iter().zip(right_line).enumerate() {
+            if l != r {
+                diffs.push(CellDiff {
+                    row,
+                    col,
+                    left: l,
+                    right: r,
+                });
+            }
+        }
+    }
+    Ok(diffs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_maze;
+
+    #[test]
+    fn reports_exactly_the_three_changed_cells() {
+        let left = read_maze("#####\n#S..#\n#.#.#\n#..G#\n#####\n".as_bytes()).unwrap();
+        let right = read_maze("#####\n#S#.#\n#...#\n#.#G#\n#####\n".as_bytes()).unwrap();
+        let diffs = diff_mazes(&left, &right).unwrap();
+        let cells: Vec<_> = diffs
+            .iter()
+            .map(|diff| (diff.row, diff.col, diff.left, diff.right))
+            .collect();
+        assert_eq!(
+            cells,
+            vec![(1, 2, '.', '#'), (2, 2, '#', '.'), (3, 2, '.', '#')]
+        );
+    }
+
+    #[test]
+    fn rejects_mazes_of_different_sizes() {
+        let left = read_maze("###\n#S#\n###\n".as_bytes()).unwrap();
+        let right = read_maze("####\n#SG#\n####\n".as_bytes()).unwrap();
+        assert!(matches!(
+            diff_mazes(&left, &right),
+            Err(MazeError::DimensionMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,117 @@
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{find_start, Maze};
+
+/// Derives a variant of `base` that keeps every cell within `radius` (Euclidean, in
+/// cells) of the start and re-randomizes the rest. Rooms sit on odd coordinates as in
+/// the generated text mazes; the walls between them outside the core are rebuilt with
+/// a seeded Kruskal pass on top of the passages the core already has, so the variant
+/// stays a perfect maze. The outer border and the walls next to the start and goal are
+/// kept as well, so the entrances stay open.
+/// Variants of one base with different seeds therefore share the same core.
+pub fn derive_variant(base: &Maze, radius: f64, seed: u64) -> Maze {
+    let mut maze = Maze {
+        rows: base.rows,
+        cols: base.cols,
+        data: base.data.clone(),
+    };
+    if maze.rows < 3 || maze.cols < 3 {
+        return maze;
+    }
+
+    let start = find_start(base);
+    let in_core = |(row, col): (usize, usize)| {
+        let (dy, dx) = (row as f64 - start.0 as f64, col as f64 - start.1 as f64);
+        (dy * dy + dx * dx).sqrt() <= radius
+    };
+    let room_index = |(row, col): (usize, usize)| (row / 2) * (maze.cols / 2) + col / 2;
+    let mut parent: Vec<usize> = (0..(maze.rows / 2) * (maze.cols / 2)).collect();
+
+    // Walls between two horizontally or vertically adjacent rooms.
+    let mut candidates = Vec::new();
+    for row in 1..maze.rows - 1 {
+        for col in 1..maze.cols - 1 {
+            let rooms = match (row % 2, col % 2) {
+                (1, 0) => ((row, col - 1), (row, col + 1)),
+                (0, 1) => ((row - 1, col), (row + 1, col)),
+                _ => continue,
+            };
+            if rooms.1 .0 >= maze.rows - 1 || rooms.1 .1 >= maze.cols - 1 {
+                continue;
+            }
+            let entrance = [
+                (row - 1, col),
+                (row + 1, col),
+                (row, col - 1),
+                (row, col + 1),
+            ]
+            .iter()
+            .any(|&(r, c)| matches!(base.data[r][c], 'S' | 'G'));
+            if in_core((row, col)) || entrance {
+                if maze.data[row][col] != '#' {
+                    union(&mut parent, room_index(rooms.0), room_index(rooms.1));
+                }
+            } else {
+                candidates.push(((row, col), rooms));
+            }
+        }
+    }
+
+    candidates.shuffle(&mut StdRng::seed_from_u64(seed));
+    for ((row, col), (a, b)) in candidates {
+        maze.data[row][col] = if union(&mut parent, room_index(a), room_index(b)) {
+            '.'
+        } else {
+            '#'
+        };
+    }
+    maze
+}
+
+/// Joins the sets of `a` and `b`, returning false if they were already joined.
+fn union(parent: &mut [usize], a: usize, b: usize) -> bool {
+    let (root_a, root_b) = (find(parent, a), find(parent, b));
+    if root_a == root_b {
+        return false;
+    }
+    parent[root_a] = root_b;
+    true
+}
+
+fn find(parent: &mut [usize], mut node: usize) -> usize {
+    while parent[node] != node {
+        parent[node] = parent[parent[node]];
+        node = parent[node];
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solve_maze, test_support::generated};
+
+    #[test]
+    fn variants_share_the_core_and_differ_outside_it() {
+        let base = generated(150);
+        let radius = 6.0;
+        let first = derive_variant(&base, radius, 1);
+        let second = derive_variant(&base, radius, 2);
+
+        let start = find_start(&base);
+        let mut outside_differs = false;
+        for row in 0..base.rows {
+            for col in 0..base.cols {
+                let (dy, dx) = (row as f64 - start.0 as f64, col as f64 - start.1 as f64);
+                if (dy * dy + dx * dx).sqrt() <= radius {
+                    assert_eq!(first.data[row][col], base.data[row][col]);
+                    assert_eq!(second.data[row][col], base.data[row][col]);
+                } else {
+                    outside_differs |= first.data[row][col] != second.data[row][col];
+                }
+            }
+        }
+        assert!(outside_differs);
+        assert!(solve_maze(&first).is_some() && solve_maze(&second).is_some());
+    }
+}
@@ -0,0 +1,279 @@
+use std::{fmt, io};
+
+use image::ImageError;
+
+#[derive(Debug)]
+pub enum MazeError {
+    Io(io::Error),
+    Image(ImageError),
+    Json(serde_json::Error),
+    Encoding {
+        row: usize,
+    },
+    TabInInput {
+        row: usize,
+        col: usize,
+    },
+    InvalidRle {
+        row: usize,
+    },
+    MissingEndpoint {
+        marker: char,
+    },
+    InvalidBinary,
+    InvalidBoxAscii {
+        row: usize,
+    },
+    NonAsciiOutput {
+        row: usize,
+    },
+    InvalidJsonCell {
+        index: usize,
+    },
+    UnknownCell {
+        row: usize,
+        col: usize,
+        cell: char,
+    },
+    UnencodableCell {
+        row: usize,
+        col: usize,
+        cell: char,
+    },
+    IncompleteJsonGrid {
+        missing: usize,
+    },
+    InvalidBundleLine {
+        line: usize,
+    },
+    UnsupportedBinaryVersion {
+        version: u8,
+    },
+    DimensionMismatch {
+        left: (usize, usize),
+        right: (usize, usize),
+    },
+    CanvasTooSmall {
+        maze: (usize, usize),
+        canvas: (usize, usize),
+    },
+    EmptyMask,
+    IncompleteTileGrid {
+        tiles: usize,
+        cols: usize,
+    },
+    TileTooSmall {
+        rows: usize,
+        cols: usize,
+    },
+    NoSeamOpening {
+        row: usize,
+        col: usize,
+    },
+    InvalidSeed {
+        line: usize,
+    },
+    NoOpenCells,
+    OpenCellTargetMissed {
+        target: usize,
+        achieved: usize,
+    },
+    ConnectivityUnmet {
+        k: usize,
+        opened: usize,
+    },
+    NoSolution,
+    IsolatedStart {
+        row: usize,
+        col: usize,
+    },
+    IsolatedGoal {
+        row: usize,
+        col: usize,
+    },
+    SolutionEndpoints,
+    SolutionHitsWall {
+        row: usize,
+        col: usize,
+    },
+    SolutionJump {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    AttemptsExhausted {
+        requested: usize,
+        found: usize,
+        attempts: usize,
+    },
+    Cancelled,
+    PathBoundsUnmet {
+        attempts: usize,
+    },
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::Io(err) => write!(f, "I/O error: {}", err),
+            MazeError::Image(err) => write!(f, "image error: {}", err),
+            MazeError::Json(err) => write!(f, "JSON error: {}", err),
+            MazeError::Encoding { row } => {
+                write!(f, "maze input is not valid UTF-8 at row {}", row)
+            }
+            MazeError::TabInInput { row, col } => {
+                write!(
+                    f,
+                    "tab character in maze input at row {}, column {}",
+                    row, col
+                )
+            }
+            MazeError::InvalidRle { row } => {
+                write!(f, "run lengths of row {} do not match the maze width", row)
+            }
+            MazeError::MissingEndpoint { marker } => write!(f, "maze has no '{}' cell", marker),
+            MazeError::InvalidBinary => write!(f, "not a valid binary maze file"),
+            MazeError::InvalidBoxAscii { row } => {
+                write!(f, "row {} does not fit the box-style maze layout", row)
+            }
+            MazeError::NonAsciiOutput { row } => {
+                write!(f, "row {} of the output contains non-ASCII characters", row)
+            }
+            MazeError::InvalidJsonCell { index } => write!(
+                f,
+                "cell {} of the JSON maze is out of bounds, repeated or of unknown type",
+                index
+            ),
+            MazeError::UnknownCell { row, col, cell } => write!(
+                f,
+                "cell '{}' at row {}, column {} has no cell type code",
+                cell, row, col
+            ),
+            MazeError::UnencodableCell { row, col, cell } => write!(
+                f,
+                "cell '{}' at row {}, column {} cannot be written in this format",
+                cell, row, col
+            ),
+            MazeError::IncompleteJsonGrid { missing } => {
+                write!(f, "JSON maze is missing {} cells", missing)
+            }
+            MazeError::InvalidBundleLine { line } => {
+                write!(f, "line {} of the bundle is not a valid maze", line)
+            }
+            MazeError::UnsupportedBinaryVersion { version } => {
+                write!(f, "binary maze format version {} is not supported", version)
+            }
+            MazeError::DimensionMismatch { left, right } => write!(
+                f,
+                "maze dimensions differ: {}x{} vs {}x{}",
+                left.0, left.1, right.0, right.1
+            ),
+            MazeError::CanvasTooSmall { maze, canvas } => write!(
+                f,
+                "{}x{} maze does not fit on a {}x{} canvas",
+                maze.0, maze.1, canvas.0, canvas.1
+            ),
+            MazeError::EmptyMask => {
+                write!(f, "mask leaves no room for a start and a goal")
+            }
+            MazeError::NoOpenCells => write!(f, "no open cell left for the start or goal"),
+            MazeError::IncompleteTileGrid { tiles, cols } => write!(
+                f,
+                "{} tiles do not fill complete rows of {} tiles",
+                tiles, cols
+            ),
+            MazeError::TileTooSmall { rows, cols } => write!(
+                f,
+                "tiles of {}x{} are too small to stitch; they need at least 3x3 cells",
+                rows, cols
+            ),
+            MazeError::NoSeamOpening { row, col } => write!(
+                f,
+                "no cell on the seam at row {}, column {} has open cells on both sides",
+                row, col
+            ),
+            MazeError::InvalidSeed { line } => {
+                write!(f, "line {} of the seed file is not a valid seed", line)
+            }
+            MazeError::OpenCellTargetMissed { target, achieved } => write!(
+                f,
+                "could not get closer than {} open cells to the target of {}",
+                achieved, target
+            ),
+            MazeError::ConnectivityUnmet { k, opened } => write!(
+                f,
+                "could not make the maze {}-connected after opening {} walls",
+                k, opened
+            ),
+            MazeError::NoSolution => write!(f, "no path from the start to a goal"),
+            MazeError::IsolatedStart { row, col } => write!(
+                f,
+                "start at row {}, column {} has no open neighbour",
+                row, col
+            ),
+            MazeError::IsolatedGoal { row, col } => write!(
+                f,
+                "goal at row {}, column {} has no open neighbour",
+                row, col
+            ),
+            MazeError::SolutionEndpoints => {
+                write!(f, "solution does not run from the start to a goal")
+            }
+            MazeError::SolutionHitsWall { row, col } => {
+                write!(
+                    f,
+                    "solution passes through a wall at row {}, column {}",
+                    row, col
+                )
+            }
+            MazeError::SolutionJump { from, to } => write!(
+                f,
+                "solution jumps from {:?} to {:?}, which are not adjacent",
+                from, to
+            ),
+            MazeError::AttemptsExhausted {
+                requested,
+                found,
+                attempts,
+            } => write!(
+                f,
+                "found only {} of {} unique mazes after {} attempts",
+                found, requested, attempts
+            ),
+            MazeError::PathBoundsUnmet { attempts } => write!(
+                f,
+                "no maze within the path length bounds after {} attempts",
+                attempts
+            ),
+            MazeError::Cancelled => write!(f, "operation was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for MazeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MazeError::Io(err) => Some(err),
+            MazeError::Image(err) => Some(err),
+            MazeError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MazeError {
+    fn from(err: io::Error) -> Self {
+        MazeError::Io(err)
+    }
+}
+
+impl From<ImageError> for MazeError {
+    fn from(err: ImageError) -> Self {
+        MazeError::Image(err)
+    }
+}
+
+impl From<serde_json::Error> for MazeError {
+    fn from(err: serde_json::Error) -> Self {
+        MazeError::Json(err)
+    }
+}
@@ -0,0 +1,75 @@
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+use crate::{
+    algorithm::GenerationAlgorithm, error::MazeError, generate_maze, mask::carve_masked_with_rng,
+    render_maze, transform::adjust_dead_ends, Maze, SIZE,
+};
+
+/// Generation settings for library users who want every random choice to come from one
+/// reproducible stream: the carve, start and goal placement, masked carving and braiding.
+pub struct GeneratorConfig {
+    pub algorithm: GenerationAlgorithm,
+    rng: Box<dyn RngCore>,
+}
+
+impl GeneratorConfig {
+    pub fn new(algorithm: GenerationAlgorithm, rng: Box<dyn RngCore>) -> Self {
+        GeneratorConfig { algorithm, rng }
+    }
+
+    pub fn from_seed(algorithm: GenerationAlgorithm, seed: u64) -> Self {
+        Self::new(algorithm, Box::new(StdRng::seed_from_u64(seed)))
+    }
+
+    /// The shared stream, for post-processing such as the [`crate::transform`] functions.
+    pub fn rng(&mut self) -> &mut dyn RngCore {
+        self.rng.as_mut()
+    }
+
+    /// Generates and renders a maze. knossos only accepts seeds, so the carve and the
+    /// start and goal placement each take the next value of the stream as theirs.
+    pub fn generate(&mut self) -> Result<Maze, MazeError> {
+        let carve_seed = self.rng.next_u64();
+        let placement_seed = self.rng.next_u64();
+        render_maze(
+            &generate_maze(self.algorithm, SIZE, SIZE, carve_seed),
+            placement_seed,
+        )
+    }
+
+    /// Carves a maze inside `allowed`, as [`crate::mask::carve_masked`] does.
+    pub fn carve_masked(&mut self, allowed: &[Vec<bool>]) -> Result<Maze, MazeError> {
+        carve_masked_with_rng(allowed, self.rng())
+    }
+
+    /// Braids or adds spurs until `maze` has `target` dead ends, as
+    /// [`adjust_dead_ends`] does, and returns the count reached.
+    pub fn braid(&mut self, maze: &mut Maze, target: usize) -> usize {
+        adjust_dead_ends(maze, target, self.rng())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn braided(seed: u64) -> (String, usize, String) {
+        let mut config = GeneratorConfig::new(
+            GenerationAlgorithm::GrowingTree,
+            Box::new(StdRng::seed_from_u64(seed)),
+        );
+        let mut maze = config.generate().unwrap();
+        let generated = maze.to_string();
+        let dead_ends = config.braid(&mut maze, 3);
+        (generated, dead_ends, maze.to_string())
+    }
+
+    #[test]
+    fn a_fixed_rng_reproduces_the_maze_and_the_braiding() {
+        let first = braided(158);
+        assert_eq!(first, braided(158));
+        assert_eq!(first.1, 3);
+        assert_ne!(first.0, first.2);
+        assert_ne!(first.0, braided(159).0);
+    }
+}
@@ -0,0 +1,1576 @@
+pub mod algorithm;
+pub mod animation;
+pub mod ascii;
+pub mod batch;
+pub mod binary;
+pub mod checksum;
+pub mod composite;
+pub mod connectivity;
+pub mod diff;
+pub mod ensemble;
+pub mod error;
+pub mod generator;
+pub mod manifest;
+pub mod mask;
+pub mod navmesh;
+#[cfg(feature = "pathfinding-backend")]
+pub mod pathfinding_backend;
+pub mod polyline;
+pub mod profile;
+pub mod render;
+pub mod retry;
+pub mod rle;
+pub mod rules;
+pub mod smooth;
+pub mod solver;
+pub mod stats;
+pub mod summary;
+pub mod template;
+#[cfg(test)]
+mod test_support;
+pub mod tiled;
+pub mod transform;
+pub mod walls;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    fmt,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, ErrorKind, Write},
+};
+
+use knossos::maze::{
+    formatters::{Formatter, StringWrapper},
+    *,
+};
+use serde::{Deserialize, Serialize};
+
+use algorithm::GenerationAlgorithm;
+use error::MazeError;
+use navmesh::{to_navmesh, NavEdge};
+use retry::{write_file, RetryPolicy};
+use smooth::smooth_path;
+use solver::{bfs_tree, region_tags, through_route_cells, DirectionOrder, SolverAlgorithm};
+use stats::{compute_stats, corridor_width_profile, count_shortest_paths, Stats};
+use walls::Rect;
+
+pub const SIZE: usize = 10;
+
+pub struct Maze {
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<Vec<char>>,
+}
+
+impl Maze {
+    pub fn open_cell_count(&self) -> usize {
+        self.data
+            .iter()
+            .map(|row| row.iter().filter(|&&c| c != '#').count())
+            .sum()
+    }
+
+    /// Fraction of cells that are not walls; zero for an empty grid.
+    pub fn open_density(&self) -> f64 {
+        let total = self.rows * self.cols;
+        if total == 0 {
+            0.0
+        } else {
+            self.open_cell_count() as f64 / total as f64
+        }
+    }
+
+    /// Flattens the grid row by row into one code per cell from `mapping` (usually
+    /// [`CELL_MAPPINGS`]), for handing to C or GPU code. Cells the mapping does not
+    /// cover become `u8::MAX`. Returns the buffer with the width and height.
+    pub fn to_grid_of_u8(&self, mapping: &[CellMapping]) -> (Vec<u8>, usize, usize) {
+        let mut grid = Vec::with_capacity(self.rows * self.cols);
+        for line in &self.data {
+            grid.extend(
+                line.iter()
+                    .map(|&cell| cell_code(cell, mapping).unwrap_or(u8::MAX)),
+            );
+        }
+        (grid, self.cols, self.rows)
+    }
+
+    /// FNV-1a over the grid, stable across runs so it can identify duplicate mazes.
+    pub fn content_hash(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for row in &self.data {
+            for c in row.iter().chain(std::iter::once(&'\n')) {
+                for byte in (*c as u32).to_le_bytes() {
+                    hash ^= byte as u64;
+                    hash = hash.wrapping_mul(0x0100_0000_01b3);
+                }
+            }
+        }
+        hash
+    }
+
+    /// Compares the two grids by wall, floor, start and goal only, so solution markers
+    /// such as `*`, weight digits and other annotations on floor cells are ignored.
+    pub fn eq_ignoring_solution(&self, other: &Maze) -> bool {
+        let classify = |c: char| match c {
+            '#' | 'S' | 'G' => c,
+            _ => '.',
+        };
+        self.rows == other.rows
+            && self.cols == other.cols
+            && self.data.len() == other.data.len()
+            && self.data.iter().zip(&other.data).all(|(left, right)| {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .zip(right)
+                        .all(|(&a, &b)| classify(a) == classify(b))
+            })
+    }
+}
+
+/// One orthogonal step on the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum Move {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Move {
+    /// Every move, in the order the solvers try neighbours.
+    pub const ALL: [Move; 4] = [Move::Up, Move::Down, Move::Left, Move::Right];
+
+    /// (row, col) offset of the move.
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Move::Up => (-1, 0),
+            Move::Down => (1, 0),
+            Move::Left => (0, -1),
+            Move::Right => (0, 1),
+        }
+    }
+
+    pub fn letter(self) -> char {
+        match self {
+            Move::Up => 'U',
+            Move::Down => 'D',
+            Move::Left => 'L',
+            Move::Right => 'R',
+        }
+    }
+
+    /// The move from `from` to an orthogonally adjacent `to`.
+    pub fn between(from: (usize, usize), to: (usize, usize)) -> Option<Move> {
+        let offset = (to.0 as i32 - from.0 as i32, to.1 as i32 - from.1 as i32);
+        Move::ALL.into_iter().find(|step| step.offset() == offset)
+    }
+}
+
+/// Turns a path into the moves that walk it; stops at the first non-adjacent step.
+pub fn path_to_moves(path: &[(usize, usize)]) -> Vec<Move> {
+    path.windows(2)
+        .map_while(|step| Move::between(step[0], step[1]))
+        .collect()
+}
+
+impl Maze {
+    /// Open neighbours of `cell` paired with the move that reaches them, in
+    /// [`Move::ALL`] order.
+    pub fn open_neighbors_dir(&self, (row, col): (usize, usize)) -> Vec<((usize, usize), Move)> {
+        Move::ALL
+            .into_iter()
+            .filter_map(|step| {
+                let (dr, dc) = step.offset();
+                let (new_row, new_col) = (row as i32 + dr, col as i32 + dc);
+                if new_row < 0 || new_col < 0 {
+                    return None;
+                }
+                let next = (new_row as usize, new_col as usize);
+                let cell = *self.data.get(next.0)?.get(next.1)?;
+                (cell != '#').then_some((next, step))
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.data {
+            for &cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let open_cells = self.open_cell_count();
+        f.debug_struct("Maze")
+            .field("rows", &self.rows)
+            .field("cols", &self.cols)
+            .field("open_cells", &open_cells)
+            .field("walls", &(self.rows * self.cols - open_cells))
+            .field("goals", &find_goals(self).len())
+            .finish()
+    }
+}
+
+#[derive(Serialize)]
+struct Cell {
+    x: usize,
+    y: usize,
+    #[serde(rename = "type")]
+    cell_type: u8,
+    weight: u32,
+    /// Whether the cell lies on some path from the start to a goal.
+    on_through_route: bool,
+}
+
+/// How one kind of cell is written in text and JSON, with a display name and suggested
+/// colour for renderers.
+pub struct CellMapping {
+    pub symbol: char,
+    pub code: u8,
+    pub name: &'static str,
+    pub color: &'static str,
+}
+
+/// Every cell kind, in `type` code order. Weighted floor digits map to `floor`; `D` and
+/// `~` are the door and river cells that a [`rules::RuleTable`] gives meaning to.
+pub const CELL_MAPPINGS: [CellMapping; 6] = [
+    CellMapping {
+        symbol: 'S',
+        code: 0,
+        name: "start",
+        color: "#00aa00",
+    },
+    CellMapping {
+        symbol: 'G',
+        code: 1,
+        name: "goal",
+        color: "#dc0000",
+    },
+    CellMapping {
+        symbol: '.',
+        code: 2,
+        name: "floor",
+        color: "#ffffff",
+    },
+    CellMapping {
+        symbol: '#',
+        code: 3,
+        name: "wall",
+        color: "#282828",
+    },
+    CellMapping {
+        symbol: 'D',
+        code: 4,
+        name: "door",
+        color: "#8b5a2b",
+    },
+    CellMapping {
+        symbol: '~',
+        code: 5,
+        name: "river",
+        color: "#1e64c8",
+    },
+];
+
+#[derive(Serialize)]
+struct LegendEntry {
+    name: &'static str,
+    color: &'static str,
+}
+
+#[derive(Serialize)]
+struct MazeJson {
+    width: usize,
+    height: usize,
+    legend: BTreeMap<u8, LegendEntry>,
+    start: Position,
+    goal: Position,
+    maze: Vec<Cell>,
+    solution: Vec<Position>,
+    /// Indices of the solution cells into `maze`, following its layout.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solution_indices: Option<Vec<usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solution_sampled: Option<Vec<Position>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    smoothed_solution: Option<Vec<Position>>,
+    /// Cells in the order the generator carved them, before any transform.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    build_steps: Option<Vec<Position>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tree: Option<Vec<TreeEdge>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    polylines: Option<Vec<Vec<Position>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    navmesh: Option<NavmeshJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wall_rects: Option<Vec<Rect>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    regions: Option<Vec<RegionTag>>,
+    stats: Stats,
+    meta: Meta,
+}
+
+#[derive(Serialize)]
+struct SolutionJson {
+    start: Position,
+    goal: Position,
+    solution: Vec<Position>,
+    path_length: usize,
+}
+
+/// The cells as one row-major array of `type` codes per row, for matrix renderers.
+#[derive(Serialize)]
+struct GridJson {
+    width: usize,
+    height: usize,
+    start: Position,
+    goal: Position,
+    maze: Vec<Vec<u8>>,
+    solution: Vec<Position>,
+}
+
+#[derive(Serialize)]
+struct RegionTag {
+    x: usize,
+    y: usize,
+    region: u32,
+}
+
+/// [`navmesh::NavGraph`] with node positions; edges refer to nodes by index.
+#[derive(Serialize)]
+struct NavmeshJson {
+    nodes: Vec<Position>,
+    edges: Vec<NavEdge>,
+}
+
+#[derive(Serialize)]
+struct TreeEdge {
+    child: Position,
+    parent: Position,
+}
+
+#[derive(Serialize)]
+struct Meta {
+    layout: Layout,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    algorithm: Option<GenerationAlgorithm>,
+}
+
+#[derive(Serialize, Clone, Copy, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Layout {
+    #[default]
+    RowMajor,
+    ColumnMajor,
+}
+
+#[derive(Default)]
+pub struct JsonOptions {
+    pub layout: Layout,
+    pub algorithm: Option<GenerationAlgorithm>,
+    pub tree: bool,
+    pub polylines: bool,
+    pub navmesh: bool,
+    pub wall_rects: bool,
+    pub count_solutions: bool,
+    /// Fill `stats.corridor_width_profile` along the solution.
+    pub corridor_widths: bool,
+    pub smooth: bool,
+    /// Band width for the `regions` field; `None` leaves it out.
+    pub region_band: Option<usize>,
+    pub solution_indices: bool,
+    /// Emit every k-th solution cell under `solution_sampled`.
+    pub sample_every: Option<usize>,
+    /// Solver and expansion count for `stats`, usually from a [`solver::Solution`].
+    pub solver: Option<SolverAlgorithm>,
+    pub expanded_nodes: Option<usize>,
+    /// Carve steps from [`mask::carve_masked_recorded`] for the `build_steps` field.
+    pub build_steps: Option<Vec<(usize, usize)>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Position {
+    x: usize,
+    y: usize,
+}
+
+impl From<(usize, usize)> for Position {
+    /// Converts a `(row, col)` tuple, the order used by the reader and the solvers, into
+    /// a position with `x` as the column and `y` as the row.
+    fn from((row, col): (usize, usize)) -> Self {
+        Position { x: col, y: row }
+    }
+}
+
+/// Carves a `width` x `height`-room maze, which renders to a grid of `2 * width + 1` by
+/// `2 * height + 1` cells.
+pub fn generate_maze(
+    algorithm: GenerationAlgorithm,
+    width: usize,
+    height: usize,
+    seed: u64,
+) -> OrthogonalMaze {
+    OrthogonalMazeBuilder::new()
+        .height(height)
+        .width(width)
+        .algorithm(algorithm.build())
+        .seed(Some(seed))
+        .build()
+}
+
+pub fn game_map(seed: u64) -> impl Formatter<StringWrapper> {
+    GameMap::new().span(1).with_start_goal().seed(Some(seed))
+}
+
+pub fn render_maze(maze: &OrthogonalMaze, seed: u64) -> Result<Maze, MazeError> {
+    let text = maze.format(game_map(seed)).into_inner();
+    read_maze(text.as_bytes())
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReadMode {
+    /// Keep every character as read.
+    #[default]
+    Strict,
+    /// Treat any character other than `#`, `S`, `G` and weight digits as floor.
+    Lenient,
+}
+
+pub fn read_maze_from_file(filename: &str) -> Result<Maze, MazeError> {
+    read_maze_from_file_with_mode(filename, ReadMode::Strict)
+}
+
+pub fn read_maze_from_file_with_mode(filename: &str, mode: ReadMode) -> Result<Maze, MazeError> {
+    let file = File::open(filename)?;
+    read_maze_with_progress(BufReader::new(file), mode, 0, |_| {})
+}
+
+pub fn read_maze<R: BufRead>(reader: R) -> Result<Maze, MazeError> {
+    read_maze_with_progress(reader, ReadMode::Strict, 0, |_| {})
+}
+
+/// Byte order mark some editors put at the start of UTF-8 files; stripped on read.
+const BOM: char = '\u{feff}';
+
+/// Like [`read_maze`], but calls `on_progress` with the number of rows read so far after
+/// every `every` rows. An `every` of zero never reports. Box-style knossos output is
+/// detected from the first line and read whole by [`ascii::parse_box_style`].
+pub fn read_maze_with_progress<R: BufRead, F: FnMut(usize)>(
+    reader: R,
+    mode: ReadMode,
+    every: usize,
+    mut on_progress: F,
+) -> Result<Maze, MazeError> {
+    let mut rows = 0;
+    let mut data = Vec::new();
+    let mut lines = reader
+        .lines()
+        .enumerate()
+        .map(|(row, line)| match line {
+            Ok(mut line) => {
+                if row == 0 && line.starts_with(BOM) {
+                    line.replace_range(..BOM.len_utf8(), "");
+                }
+                Ok(line)
+            }
+            Err(err) if err.kind() == ErrorKind::InvalidData => Err(MazeError::Encoding { row }),
+            Err(err) => Err(MazeError::Io(err)),
+        })
+        .peekable();
+
+    if let Some(Ok(first)) = lines.peek() {
+        if ascii::is_box_style(first) {
+            let lines = lines.collect::<Result<Vec<_>, _>>()?;
+            return ascii::parse_box_style(&lines);
+        }
+    }
+
+    for line in lines {
+        let line = line?;
+        if let Some(col) = line.chars().position(|c| c == '\t') {
+            return Err(MazeError::TabInInput { row: rows, col });
+        }
+        let chars: Vec<char> = match mode {
+            ReadMode::Strict => line.chars().collect(),
+            ReadMode::Lenient => line
+                .chars()
+                .map(|c| match c {
+                    '#' | 'S' | 'G' | '1'..='9' => c,
+                    _ => '.',
+                })
+                .collect(),
+        };
+        data.push(chars);
+        rows += 1;
+        if every > 0 && rows % every == 0 {
+            on_progress(rows);
+        }
+    }
+
+    // Blank lines after the grid, e.g. an extra newline at the end of the file, would
+    // otherwise become an empty last row and leave the maze zero cells wide.
+    while data.last().is_some_and(Vec::is_empty) {
+        data.pop();
+    }
+    Ok(Maze {
+        rows: data.len(),
+        cols: data.last().map_or(0, Vec::len),
+        data,
+    })
+}
+
+pub fn solve_maze(maze: &Maze) -> Option<Vec<(usize, usize)>> {
+    solve_maze_ordered(maze, &DirectionOrder::default())
+}
+
+/// Depth-first search that tries neighbours in `order`, so the same maze and order
+/// always give the same path.
+pub fn solve_maze_ordered(maze: &Maze, order: &DirectionOrder) -> Option<Vec<(usize, usize)>> {
+    dfs_expanding(maze, order).0
+}
+
+/// [`solve_maze_ordered`] that also returns how many cells were popped off the stack.
+pub(crate) fn dfs_expanding(
+    maze: &Maze,
+    order: &DirectionOrder,
+) -> (Option<Vec<(usize, usize)>>, usize) {
+    let open_cells = maze.open_cell_count();
+    let mut visited = HashSet::with_capacity(open_cells);
+    let mut stack = VecDeque::new();
+    let mut parents = HashMap::with_capacity(open_cells);
+
+    let start = find_start(maze);
+    stack.push_back(start);
+    visited.insert(start);
+    let mut expanded = 0;
+
+    while let Some((row, col)) = stack.pop_back() {
+        expanded += 1;
+        if maze.data[row][col] == 'G' {
+            return (Some(construct_path((row, col), &parents)), expanded);
+        }
+
+        // The stack pops the last push first, so push the preferred direction last.
+        for (dr, dc) in order.0.iter().rev() {
+            let (new_row, new_col) = (row as i32 + dr, col as i32 + dc);
+            if new_row >= 0
+                && new_row < maze.rows as i32
+                && new_col >= 0
+                && new_col < maze.cols as i32
+                && maze.data[new_row as usize][new_col as usize] != '#'
+                && !visited.contains(&(new_row as usize, new_col as usize))
+            {
+                stack.push_back((new_row as usize, new_col as usize));
+                visited.insert((new_row as usize, new_col as usize));
+                parents.insert((new_row as usize, new_col as usize), (row, col));
+            }
+        }
+    }
+
+    (None, expanded)
+}
+
+pub fn find_start(maze: &Maze) -> (usize, usize) {
+    for (i, row) in maze.data.iter().enumerate() {
+        for (j, &c) in row.iter().enumerate() {
+            if c == 'S' {
+                return (i, j);
+            }
+        }
+    }
+    panic!("No starting point 'S' found in the maze.");
+}
+
+pub fn find_goals(maze: &Maze) -> Vec<(usize, usize)> {
+    maze.data
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.iter()
+                .enumerate()
+                .filter(|(_, &c)| c == 'G')
+                .map(move |(col, _)| (row, col))
+        })
+        .collect()
+}
+
+pub fn construct_path(
+    goal: (usize, usize),
+    parents: &HashMap<(usize, usize), (usize, usize)>,
+) -> Vec<(usize, usize)> {
+    let mut path = Vec::new();
+    let mut current = goal;
+    while let Some(&parent) = parents.get(&current) {
+        path.push(current);
+        current = parent;
+    }
+    path.push(current);
+    path.reverse();
+    path
+}
+
+/// Writes the maze as JSON and returns the bytes written.
+pub fn create_json_file(
+    width: usize,
+    height: usize,
+    maze: &Maze,
+    solution: &[(usize, usize)],
+    filename: &str,
+    options: &JsonOptions,
+    retry: &RetryPolicy,
+) -> Result<Vec<u8>, MazeError> {
+    let json_string = maze_json_string(width, height, maze, solution, options)?;
+
+    write_file(filename, json_string.as_bytes(), retry)?;
+    Ok(json_string.into_bytes())
+}
+
+/// Serializes the maze exactly as [`create_json_file`] writes it.
+pub fn maze_json_string(
+    width: usize,
+    height: usize,
+    maze: &Maze,
+    solution: &[(usize, usize)],
+    options: &JsonOptions,
+) -> Result<String, MazeError> {
+    Ok(serde_json::to_string_pretty(&maze_json(
+        width, height, maze, solution, options,
+    )?)?)
+}
+
+/// The part of the [`create_json_file`] output that [`read_maze_from_json`] needs.
+#[derive(Deserialize)]
+struct JsonGrid {
+    width: usize,
+    height: usize,
+    maze: Vec<JsonCell>,
+}
+
+#[derive(Deserialize)]
+struct JsonCell {
+    x: usize,
+    y: usize,
+    #[serde(rename = "type")]
+    cell_type: u8,
+    weight: u32,
+}
+
+/// Parses a maze back from the JSON [`create_json_file`] writes. Cell types are looked
+/// up in [`CELL_MAPPINGS`] and floor weights above 1 become digits again; every cell of
+/// the grid must appear exactly once.
+pub fn read_maze_from_json(json: &str) -> Result<Maze, MazeError> {
+    let grid: JsonGrid = serde_json::from_str(json)?;
+    let mut cells = vec![vec![None; grid.width]; grid.height];
+    for (index, cell) in grid.maze.iter().enumerate() {
+        let symbol = CELL_MAPPINGS
+            .iter()
+            .find(|mapping| mapping.code == cell.cell_type)
+            .map(|mapping| match mapping.symbol {
+                '.' if (2..=9).contains(&cell.weight) => char::from_digit(cell.weight, 10).unwrap(),
+                symbol => symbol,
+            });
+        match (
+            symbol,
+            cells.get_mut(cell.y).and_then(|row| row.get_mut(cell.x)),
+        ) {
+            (Some(symbol), Some(slot @ None)) => *slot = Some(symbol),
+            _ => return Err(MazeError::InvalidJsonCell { index }),
+        }
+    }
+
+    let missing = cells.iter().flatten().filter(|cell| cell.is_none()).count();
+    if missing > 0 {
+        return Err(MazeError::IncompleteJsonGrid { missing });
+    }
+    let data = cells
+        .into_iter()
+        .map(|row| row.into_iter().flatten().collect())
+        .collect();
+    Ok(Maze {
+        rows: grid.height,
+        cols: grid.width,
+        data,
+    })
+}
+
+/// Writes `maze` as JSON, reads it back with [`read_maze_from_json`] and writes it again,
+/// failing unless both documents are identical. Exposed so downstream code can check
+/// that its own mazes survive the format; `maze` needs a start, a goal and only cells
+/// from [`CELL_MAPPINGS`].
+pub fn roundtrip_check(maze: &Maze) -> Result<(), String> {
+    for (row, line) in maze.data.iter().enumerate() {
+        if let Some(col) = line
+            .iter()
+            .position(|&c| cell_code(c, &CELL_MAPPINGS).is_none())
+        {
+            return Err(format!(
+                "cell '{}' at row {}, column {} has no JSON type",
+                line[col], row, col
+            ));
+        }
+    }
+    let flat = || maze.data.iter().flatten();
+    if !flat().any(|&c| c == 'S') || !flat().any(|&c| c == 'G') {
+        return Err("maze needs a start and a goal".to_string());
+    }
+
+    let solution = solve_maze(maze).unwrap_or_default();
+    let options = JsonOptions::default();
+    let first = maze_json_string(maze.cols, maze.rows, maze, &solution, &options)
+        .map_err(|e| e.to_string())?;
+    let parsed = read_maze_from_json(&first).map_err(|e| e.to_string())?;
+    let second = maze_json_string(parsed.cols, parsed.rows, &parsed, &solution, &options)
+        .map_err(|e| e.to_string())?;
+    if first == second {
+        Ok(())
+    } else {
+        Err("JSON changed after a round trip".to_string())
+    }
+}
+
+fn maze_json(
+    width: usize,
+    height: usize,
+    maze: &Maze,
+    solution: &[(usize, usize)],
+    options: &JsonOptions,
+) -> Result<MazeJson, MazeError> {
+    let route = through_route_cells(maze);
+    let cell = |y: usize, x: usize| {
+        Ok(Cell {
+            x,
+            y,
+            cell_type: cell_type(maze, y, x)?,
+            weight: cell_weight(maze.data[y][x]),
+            on_through_route: route.contains(&(y, x)),
+        })
+    };
+    let maze_cells = match options.layout {
+        Layout::RowMajor => maze
+            .data
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| (0..row.len()).map(move |x| (y, x)))
+            .map(|(y, x)| cell(y, x))
+            .collect::<Result<Vec<_>, MazeError>>()?,
+        Layout::ColumnMajor => (0..maze.cols)
+            .flat_map(|x| (0..maze.rows).map(move |y| (y, x)))
+            .map(|(y, x)| cell(y, x))
+            .collect::<Result<Vec<_>, MazeError>>()?,
+    };
+
+    let solution_cells = solution
+        .iter()
+        .map(|&cell| Position::from(cell))
+        .collect::<Vec<_>>();
+
+    let start: (usize, usize) = find_start(maze);
+    let tree = options.tree.then(|| {
+        let mut edges: Vec<_> = bfs_tree(maze, start).parents.into_iter().collect();
+        edges.sort();
+        edges
+            .into_iter()
+            .map(|(child, parent)| TreeEdge {
+                child: Position::from(child),
+                parent: Position::from(parent),
+            })
+            .collect()
+    });
+    let polylines = options.polylines.then(|| {
+        maze.corridors_as_polylines()
+            .into_iter()
+            .map(|line| line.into_iter().map(Position::from).collect())
+            .collect()
+    });
+    let navmesh = options.navmesh.then(|| {
+        let graph = to_navmesh(maze);
+        NavmeshJson {
+            nodes: graph.nodes.into_iter().map(Position::from).collect(),
+            edges: graph.edges,
+        }
+    });
+    let wall_rects = options.wall_rects.then(|| walls::wall_rects(maze));
+    let regions = options.region_band.map(|band_width| {
+        let mut tags: Vec<_> = region_tags(maze, band_width).into_iter().collect();
+        tags.sort();
+        tags.into_iter()
+            .map(|((y, x), region)| RegionTag { x, y, region })
+            .collect()
+    });
+    let goal: (usize, usize) = match solution.last() {
+        Some(&goal) => goal,
+        None => maze
+            .data
+            .iter()
+            .enumerate()
+            .find_map(|(y, row)| row.iter().position(|&c| c == 'G').map(|x| (y, x)))
+            .unwrap(),
+    };
+
+    let mut stats = compute_stats(maze, solution);
+    if options.count_solutions {
+        stats.shortest_path_count = Some(count_shortest_paths(maze));
+    }
+    if options.corridor_widths {
+        stats.corridor_width_profile = Some(corridor_width_profile(maze, solution));
+    }
+    stats.solver = options.solver;
+    stats.expanded_nodes = options.expanded_nodes;
+
+    Ok(MazeJson {
+        width,
+        height,
+        legend: CELL_MAPPINGS
+            .iter()
+            .map(|mapping| {
+                (
+                    mapping.code,
+                    LegendEntry {
+                        name: mapping.name,
+                        color: mapping.color,
+                    },
+                )
+            })
+            .collect(),
+        start: Position::from(start),
+        goal: Position::from(goal),
+        maze: maze_cells,
+        solution: solution_cells,
+        solution_indices: options.solution_indices.then(|| {
+            solution
+                .iter()
+                .map(|&(y, x)| match options.layout {
+                    Layout::RowMajor => y * maze.cols + x,
+                    Layout::ColumnMajor => x * maze.rows + y,
+                })
+                .collect()
+        }),
+        solution_sampled: options.sample_every.map(|k| {
+            sample_path(solution, k)
+                .into_iter()
+                .map(Position::from)
+                .collect()
+        }),
+        smoothed_solution: options.smooth.then(|| {
+            smooth_path(maze, solution)
+                .into_iter()
+                .map(Position::from)
+                .collect()
+        }),
+        build_steps: options
+            .build_steps
+            .as_ref()
+            .map(|steps| steps.iter().map(|&cell| Position::from(cell)).collect()),
+        tree,
+        polylines,
+        navmesh,
+        wall_rects,
+        regions,
+        stats,
+        meta: Meta {
+            layout: options.layout,
+            algorithm: options.algorithm,
+        },
+    })
+}
+
+/// Every `k`-th cell of `path` starting with the first, plus the last cell if the step
+/// skipped it, so both endpoints are always present. A `k` of zero is treated as one.
+pub fn sample_path(path: &[(usize, usize)], k: usize) -> Vec<(usize, usize)> {
+    let mut sampled: Vec<_> = path.iter().copied().step_by(k.max(1)).collect();
+    if let Some(&last) = path.last() {
+        if sampled.last() != Some(&last) {
+            sampled.push(last);
+        }
+    }
+    sampled
+}
+
+/// The mazes read from a JSON Lines bundle.
+pub struct Bundle {
+    pub mazes: Vec<Maze>,
+    /// Whether an incomplete last line, e.g. from an interrupted write, was dropped.
+    pub truncated: bool,
+}
+
+/// Reads every maze from a bundle written by [`append_to_bundle`]. A last line without
+/// its newline that does not parse is taken to be a cut-off write and skipped, setting
+/// [`Bundle::truncated`]; any other invalid line is an error.
+pub fn read_bundle(filename: &str) -> Result<Bundle, MazeError> {
+    scan_bundle(&std::fs::read(filename)?).map(|(bundle, _)| bundle)
+}
+
+/// Reads the bundle as [`read_bundle`] does and cuts a truncated last line off the file,
+/// so later appends start on a fresh line.
+pub fn repair_bundle(filename: &str) -> Result<Bundle, MazeError> {
+    let (bundle, valid_len) = scan_bundle(&std::fs::read(filename)?)?;
+    if bundle.truncated {
+        OpenOptions::new()
+            .write(true)
+            .open(filename)?
+            .set_len(valid_len as u64)?;
+    }
+    Ok(bundle)
+}
+
+/// Parses the bundle and returns it with the length of its valid prefix in bytes.
+fn scan_bundle(bytes: &[u8]) -> Result<(Bundle, usize), MazeError> {
+    let mut bundle = Bundle {
+        mazes: Vec::new(),
+        truncated: false,
+    };
+    let mut offset = 0;
+    for (index, line) in bytes.split_inclusive(|&byte| byte == b'\n').enumerate() {
+        let complete = line.ends_with(b"\n");
+        let text = std::str::from_utf8(line).map(str::trim);
+        match text.map_err(|_| MazeError::Encoding { row: index }) {
+            Ok("") => {}
+            parsed => match parsed.and_then(read_maze_from_json) {
+                Ok(maze) => bundle.mazes.push(maze),
+                Err(_) if !complete => {
+                    bundle.truncated = true;
+                    break;
+                }
+                Err(_) => return Err(MazeError::InvalidBundleLine { line: index + 1 }),
+            },
+        }
+        offset += line.len();
+    }
+    Ok((bundle, offset))
+}
+
+/// Appends the maze to a JSON Lines bundle as one compact [`create_json_file`] document
+/// per line, creating the file if needed. Earlier lines are never rewritten, so an
+/// interrupted batch keeps every maze that was already appended.
+pub fn append_to_bundle(
+    width: usize,
+    height: usize,
+    maze: &Maze,
+    solution: &[(usize, usize)],
+    filename: &str,
+    options: &JsonOptions,
+) -> Result<(), MazeError> {
+    let mut line = serde_json::to_string(&maze_json(width, height, maze, solution, options)?)?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(filename)?;
+    file.write_all(line.as_bytes())?;
+    Ok(file.sync_data()?)
+}
+
+/// Writes only the endpoints and the path, for clients that already have the maze, and
+/// returns the bytes written.
+pub fn create_solution_json_file(
+    maze: &Maze,
+    solution: &[(usize, usize)],
+    filename: &str,
+    retry: &RetryPolicy,
+) -> Result<Vec<u8>, std::io::Error> {
+    let start = find_start(maze);
+    let goal = solution.last().copied().unwrap_or(start);
+
+    let solution_json = SolutionJson {
+        start: Position::from(start),
+        goal: Position::from(goal),
+        solution: solution.iter().map(|&cell| Position::from(cell)).collect(),
+        path_length: solution.len(),
+    };
+
+    let json_string = serde_json::to_string_pretty(&solution_json)?;
+
+    write_file(filename, json_string.as_bytes(), retry)?;
+    Ok(json_string.into_bytes())
+}
+
+/// Writes the maze as a grid of cell types and returns the bytes written.
+pub fn create_grid_json_file(
+    maze: &Maze,
+    solution: &[(usize, usize)],
+    filename: &str,
+    retry: &RetryPolicy,
+) -> Result<Vec<u8>, MazeError> {
+    let start = find_start(maze);
+    let goal = solution
+        .last()
+        .copied()
+        .or_else(|| find_goals(maze).first().copied())
+        .unwrap_or(start);
+
+    let grid_json = GridJson {
+        width: maze.cols,
+        height: maze.rows,
+        start: Position::from(start),
+        goal: Position::from(goal),
+        maze: (0..maze.rows)
+            .map(|y| {
+                (0..maze.data[y].len())
+                    .map(|x| cell_type(maze, y, x))
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?,
+        solution: solution.iter().map(|&cell| Position::from(cell)).collect(),
+    };
+
+    let json_string = serde_json::to_string(&grid_json)?;
+
+    write_file(filename, json_string.as_bytes(), retry)?;
+    Ok(json_string.into_bytes())
+}
+
+/// Cost of entering a cell. Floor cells may be written as a digit from `1` to `9` to
+/// give them that weight; every other cell costs 1.
+pub fn cell_weight(cell: char) -> u32 {
+    match cell {
+        '1'..='9' => cell as u32 - '0' as u32,
+        _ => 1,
+    }
+}
+
+/// The [`CELL_MAPPINGS`] code of the cell at `row` and `col`.
+fn cell_type(maze: &Maze, row: usize, col: usize) -> Result<u8, MazeError> {
+    let cell = maze.data[row][col];
+    cell_code(cell, &CELL_MAPPINGS).ok_or(MazeError::UnknownCell { row, col, cell })
+}
+
+/// Looks up the code of `cell` in `mapping`; weighted floor digits use the floor entry.
+pub fn cell_code(cell: char, mapping: &[CellMapping]) -> Option<u8> {
+    let symbol = match cell {
+        '1'..='9' => '.',
+        _ => cell,
+    };
+    mapping
+        .iter()
+        .find(|entry| entry.symbol == symbol)
+        .map(|entry| entry.code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{generated, maze, no_retry, temp_path};
+
+    /// Writes `maze` with `create_json_file` and parses the file back.
+    fn json_file(
+        maze: &Maze,
+        solution: &[(usize, usize)],
+        options: &JsonOptions,
+    ) -> serde_json::Value {
+        let filename = &temp_path(&format!("lib-{}.json", maze.content_hash()));
+        let retry = no_retry();
+        create_json_file(
+            maze.cols, maze.rows, maze, solution, filename, options, &retry,
+        )
+        .unwrap();
+        let text = std::fs::read_to_string(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn tabs_in_input_are_rejected_with_their_position() {
+        let result = read_maze("#####\n#S.G#\n##\t##\n".as_bytes());
+        assert!(matches!(
+            result,
+            Err(MazeError::TabInInput { row: 2, col: 2 })
+        ));
+    }
+
+    #[test]
+    fn open_cell_count_matches_a_manual_tally() {
+        let maze = maze("#####\n#S.1#\n#.#.#\n#..G#\n#####\n");
+        assert_eq!(maze.open_cell_count(), 8);
+
+        let generated = generated(5);
+        let tally = generated
+            .data
+            .iter()
+            .flatten()
+            .filter(|&&cell| cell != '#')
+            .count();
+        assert_eq!(generated.open_cell_count(), tally);
+    }
+
+    #[test]
+    fn column_major_layout_emits_cells_column_by_column() {
+        let maze = maze("####\n#SG#\n####\n");
+        let options = JsonOptions {
+            layout: Layout::ColumnMajor,
+            ..JsonOptions::default()
+        };
+        let json = json_file(&maze, &[(1, 1), (1, 2)], &options);
+
+        let cells: Vec<(u64, u64)> = json["maze"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .take(4)
+            .map(|cell| (cell["x"].as_u64().unwrap(), cell["y"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(cells, vec![(0, 0), (0, 1), (0, 2), (1, 0)]);
+        assert_eq!(json["maze"][4]["type"], 0);
+        assert_eq!(json["meta"]["layout"], "column-major");
+    }
+
+    #[test]
+    fn progress_fires_every_n_rows() {
+        let text = "#####\n#S..#\n#.#.#\n#.#.#\n#.#.#\n#.#.#\n#.#.#\n#.#.#\n#..G#\n#####\n";
+        let mut reported = Vec::new();
+        let maze = read_maze_with_progress(text.as_bytes(), ReadMode::Strict, 3, |rows| {
+            reported.push(rows)
+        })
+        .unwrap();
+        assert_eq!(maze.rows, 10);
+        assert_eq!(reported, vec![3, 6, 9]);
+    }
+
+    #[test]
+    fn start_goal_and_solution_use_the_same_coordinates() {
+        // The goal is off the diagonal, so swapped x and y would point at a wall.
+        let maze = maze("######\n#S...#\n####G#\n######\n");
+        let solution = solve_maze(&maze).unwrap();
+        let json = json_file(&maze, &solution, &JsonOptions::default());
+
+        assert_eq!(json["start"], serde_json::json!({"x": 1, "y": 1}));
+        assert_eq!(json["goal"], serde_json::json!({"x": 4, "y": 2}));
+        assert_eq!(json["solution"][0], json["start"]);
+        assert_eq!(json["solution"][4], json["goal"]);
+    }
+
+    #[test]
+    fn tree_lists_every_reachable_cell_but_the_start_once_as_a_child() {
+        let maze = generated(9);
+        let options = JsonOptions {
+            tree: true,
+            ..JsonOptions::default()
+        };
+        let json = json_file(&maze, &[], &options);
+
+        let mut children: Vec<(u64, u64)> = json["tree"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edge| {
+                let child = &edge["child"];
+                (child["y"].as_u64().unwrap(), child["x"].as_u64().unwrap())
+            })
+            .collect();
+        let count = children.len();
+        children.sort();
+        children.dedup();
+        assert_eq!(children.len(), count);
+
+        // Every open cell of a perfect maze is reachable.
+        let start = find_start(&maze);
+        let reachable: Vec<(u64, u64)> = (0..maze.rows)
+            .flat_map(|row| (0..maze.cols).map(move |col| (row, col)))
+            .filter(|&(row, col)| maze.data[row][col] != '#' && (row, col) != start)
+            .map(|(row, col)| (row as u64, col as u64))
+            .collect();
+        assert_eq!(children, reachable);
+    }
+
+    #[test]
+    fn display_round_trips_the_input_text() {
+        let text = "#######\n#S..#.#\n#.#...#\n#...#G#\n#######\n";
+        let maze = maze(text);
+        assert_eq!(maze.to_string(), text);
+        assert_eq!(
+            format!("{:?}", maze),
+            "Maze { rows: 5, cols: 7, open_cells: 12, walls: 23, goals: 1 }"
+        );
+    }
+
+    #[test]
+    fn lenient_mode_reads_decorations_as_floor() {
+        let text = "#######\n#S~*@.#\n###x#G#\n#######\n";
+        let maze = read_maze_with_progress(text.as_bytes(), ReadMode::Lenient, 0, |_| {}).unwrap();
+        assert_eq!(maze.data[1], vec!['#', 'S', '.', '.', '.', '.', '#']);
+        assert_eq!(maze.data[2], vec!['#', '#', '#', '.', '#', 'G', '#']);
+        assert_eq!(solve_maze(&maze).unwrap().len(), 6);
+
+        let strict = read_maze(text.as_bytes()).unwrap();
+        assert_eq!(strict.data[1][3], '*');
+    }
+
+    #[test]
+    fn solution_only_json_omits_the_maze() {
+        let maze = maze("#######\n#S...G#\n#######\n");
+        let solution = solve_maze(&maze).unwrap();
+        let filename = &temp_path("solution.json");
+        let retry = no_retry();
+        create_solution_json_file(&maze, &solution, filename, &retry).unwrap();
+
+        let text = std::fs::read_to_string(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort();
+        assert_eq!(keys, ["goal", "path_length", "solution", "start"]);
+        assert_eq!(json["path_length"], 5);
+        assert!(json.get("maze").is_none());
+    }
+
+    #[test]
+    fn position_from_a_row_col_tuple() {
+        let position = Position::from((3, 7));
+        assert_eq!((position.x, position.y), (7, 3));
+        assert_eq!(
+            serde_json::to_string(&position).unwrap(),
+            r#"{"x":7,"y":3}"#
+        );
+    }
+
+    #[test]
+    fn open_density_is_the_share_of_open_cells() {
+        let maze = maze("#####\n#S.G#\n#####\n");
+        assert_eq!(maze.open_density(), 0.2);
+        let solution = solve_maze(&maze).unwrap();
+        assert_eq!(compute_stats(&maze, &solution).open_density, 0.2);
+    }
+
+    #[test]
+    fn appending_to_a_bundle_keeps_the_existing_lines() {
+        let filename = &temp_path("bundle.jsonl");
+        std::fs::write(filename, "{\"earlier\":1}\n{\"earlier\":2}\n").unwrap();
+        for seed in 0..3 {
+            let maze = generated(seed);
+            let solution = solve_maze(&maze).unwrap();
+            let options = JsonOptions::default();
+            append_to_bundle(maze.cols, maze.rows, &maze, &solution, filename, &options).unwrap();
+        }
+
+        let text = std::fs::read_to_string(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "{\"earlier\":1}");
+        for line in &lines[2..] {
+            let json: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(json["width"], 21);
+        }
+    }
+
+    #[test]
+    fn path_cost_is_the_weight_of_the_dijkstra_detour() {
+        let maze = maze("#######\n#S.9.G#\n#.###.#\n#.....#\n#######\n");
+        let solution = solver::dijkstra(&maze).unwrap();
+        let (path, cost) = (solution.path, solution.cost.unwrap());
+        assert_eq!(path.len(), 9);
+        assert_eq!(cost, 8);
+
+        let text = maze_json_string(7, 5, &maze, &path, &JsonOptions::default()).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["stats"]["path_cost"], cost);
+        let weights: HashMap<(u64, u64), u64> = json["maze"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|cell| {
+                let position = (cell["y"].as_u64().unwrap(), cell["x"].as_u64().unwrap());
+                (position, cell["weight"].as_u64().unwrap())
+            })
+            .collect();
+        assert_eq!(weights[&(1, 3)], 9);
+        let summed: u64 = path[1..]
+            .iter()
+            .map(|&(row, col)| weights[&(row as u64, col as u64)])
+            .sum();
+        assert_eq!(summed, cost);
+    }
+
+    #[test]
+    fn the_legend_names_every_cell_type_in_the_maze() {
+        let maze = maze("#######\n#S.D~G#\n#######\n");
+        let text = maze_json_string(maze.cols, maze.rows, &maze, &[], &JsonOptions::default());
+        let json: serde_json::Value = serde_json::from_str(&text.unwrap()).unwrap();
+        let legend = json["legend"].as_object().unwrap();
+        let types: HashSet<u64> = json["maze"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|cell| cell["type"].as_u64().unwrap())
+            .collect();
+        assert_eq!(types.len(), 6);
+        for code in types {
+            let entry = &legend[&code.to_string()];
+            assert!(entry["name"].is_string() && entry["color"].is_string());
+        }
+    }
+
+    #[test]
+    fn a_cross_junction_reports_all_four_directions() {
+        let maze = maze("#####\n##S##\n#...#\n##G##\n#####\n");
+        assert_eq!(
+            maze.open_neighbors_dir((2, 2)),
+            vec![
+                ((1, 2), Move::Up),
+                ((3, 2), Move::Down),
+                ((2, 1), Move::Left),
+                ((2, 3), Move::Right),
+            ]
+        );
+        assert_eq!(maze.open_neighbors_dir((1, 2)), vec![((2, 2), Move::Down)]);
+    }
+
+    #[test]
+    fn solution_indices_point_at_the_solution_cells() {
+        let maze = generated(149);
+        let solution = solve_maze(&maze).unwrap();
+        let options = JsonOptions {
+            solution_indices: true,
+            ..JsonOptions::default()
+        };
+        let text = maze_json_string(maze.cols, maze.rows, &maze, &solution, &options).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        let cells = json["maze"].as_array().unwrap();
+        let indices = json["solution_indices"].as_array().unwrap();
+        assert_eq!(indices.len(), solution.len());
+        for (index, &(row, col)) in indices.iter().zip(&solution) {
+            let cell = &cells[index.as_u64().unwrap() as usize];
+            assert_eq!(
+                (cell["y"].as_u64(), cell["x"].as_u64()),
+                (Some(row as u64), Some(col as u64))
+            );
+        }
+    }
+
+    #[test]
+    fn grid_of_u8_has_one_code_per_cell() {
+        let maze = maze("#######\n#S.D~G#\n#..3..#\n#######\n");
+        let (grid, width, height) = maze.to_grid_of_u8(&CELL_MAPPINGS);
+        assert_eq!((width, height), (7, 4));
+        assert_eq!(grid.len(), width * height);
+        let at = |row: usize, col: usize| grid[row * width + col];
+        assert_eq!(at(0, 0), 3);
+        assert_eq!(at(1, 1), 0);
+        assert_eq!(at(1, 2), 2);
+        assert_eq!(at(1, 3), 4);
+        assert_eq!(at(1, 5), 1);
+        assert_eq!(at(2, 3), 2);
+
+        let (walls_only, _, _) = maze.to_grid_of_u8(&CELL_MAPPINGS[3..4]);
+        assert_eq!((walls_only[0], walls_only[8]), (3, u8::MAX));
+    }
+
+    #[test]
+    fn sampled_solutions_keep_every_kth_cell_and_both_ends() {
+        let maze = generated(153);
+        let solution = solve_maze(&maze).unwrap();
+        for k in [1, 3, 4, 10] {
+            let sampled = sample_path(&solution, k);
+            let expected = solution.len().div_ceil(k);
+            assert!(
+                sampled.len() == expected || sampled.len() == expected + 1,
+                "{} points for k={} on {} cells",
+                sampled.len(),
+                k,
+                solution.len()
+            );
+            assert_eq!(sampled.first(), solution.first());
+            assert_eq!(sampled.last(), solution.last());
+        }
+
+        let options = JsonOptions {
+            sample_every: Some(4),
+            ..JsonOptions::default()
+        };
+        let text = maze_json_string(maze.cols, maze.rows, &maze, &solution, &options).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(
+            json["solution_sampled"].as_array().unwrap().len(),
+            sample_path(&solution, 4).len()
+        );
+        assert_eq!(json["solution"].as_array().unwrap().len(), solution.len());
+    }
+
+    #[test]
+    fn grid_json_is_a_row_major_array_of_codes() {
+        let maze = maze("#######\n#S..#.#\n###.#.#\n#....G#\n#######\n");
+        let solution = solve_maze(&maze).unwrap();
+        let filename = &temp_path("grid.json");
+        let retry = no_retry();
+        create_grid_json_file(&maze, &solution, filename, &retry).unwrap();
+
+        let text = std::fs::read_to_string(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        let grid: Vec<Vec<u8>> = serde_json::from_value(json["maze"].clone()).unwrap();
+        assert_eq!(
+            (json["width"].as_u64(), json["height"].as_u64()),
+            (Some(7), Some(5))
+        );
+        assert_eq!(grid.len(), 5);
+        assert!(grid.iter().all(|row| row.len() == 7));
+        assert_eq!(grid[1], [3, 0, 2, 2, 3, 2, 3]);
+        assert_eq!(grid[3][5], 1);
+        assert_eq!(json["solution"].as_array().unwrap().len(), solution.len());
+    }
+
+    #[test]
+    fn a_leading_bom_is_stripped() {
+        let maze = maze("\u{feff}#####\n#S.G#\n#####\n");
+        assert_eq!(maze.cols, 5);
+        assert_eq!(maze.data[0], ['#'; 5]);
+        assert_eq!(solve_maze(&maze).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn invalid_utf8_is_an_encoding_error() {
+        let result = read_maze(&b"#####\n#S\xff.G#\n#####\n"[..]);
+        assert!(matches!(result, Err(MazeError::Encoding { row: 1 })));
+    }
+
+    #[test]
+    fn in_memory_rendering_matches_the_text_file_round_trip() {
+        let seed = 169;
+        let generated = generate_maze(GenerationAlgorithm::GrowingTree, SIZE, SIZE, seed);
+        let filename = &temp_path("round-trip.txt");
+        generated.save(filename, game_map(seed)).unwrap();
+
+        let from_file = read_maze_from_file(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        let in_memory = render_maze(&generated, seed).unwrap();
+        assert_eq!(
+            (in_memory.rows, in_memory.cols),
+            (from_file.rows, from_file.cols)
+        );
+        assert_eq!(in_memory.data, from_file.data);
+    }
+
+    #[test]
+    fn solution_markers_are_ignored_when_comparing() {
+        let clean = maze("#######\n#S..#.#\n###.#.#\n#....G#\n#######\n");
+        let marked = maze("#######\n#S**#.#\n###*#.#\n#..**G#\n#######\n");
+        assert!(clean.eq_ignoring_solution(&marked));
+        assert!(marked.eq_ignoring_solution(&clean));
+
+        let walled = maze("#######\n#S..#.#\n###.#.#\n#...#G#\n#######\n");
+        assert!(!clean.eq_ignoring_solution(&walled));
+        assert!(!clean.eq_ignoring_solution(&maze("#####\n#S.G#\n#####\n")));
+    }
+
+    #[test]
+    fn door_and_river_cells_have_json_types() {
+        let maze = maze("#######\n#S.D~G#\n#######\n");
+        let json = maze_json_string(maze.cols, maze.rows, &maze, &[], &JsonOptions::default());
+        let grid = read_maze_from_json(&json.unwrap()).unwrap();
+        assert_eq!(grid.data[1], vec!['#', 'S', '.', 'D', '~', 'G', '#']);
+    }
+
+    #[test]
+    fn unknown_cells_are_an_error() {
+        let maze = maze("#####\n#S*G#\n#####\n");
+        let result = maze_json_string(maze.cols, maze.rows, &maze, &[], &JsonOptions::default());
+        assert!(matches!(
+            result,
+            Err(MazeError::UnknownCell {
+                row: 1,
+                col: 2,
+                cell: '*'
+            })
+        ));
+    }
+
+    #[test]
+    fn seeded_mazes_survive_the_json_round_trip() {
+        for seed in 0..5 {
+            let maze = generated(seed);
+            assert_eq!(roundtrip_check(&maze), Ok(()), "seed {}", seed);
+        }
+        assert_eq!(
+            roundtrip_check(&maze("#######\n#S.D~G#\n#######\n")),
+            Ok(())
+        );
+        assert!(roundtrip_check(&maze("#####\n#S*G#\n#####\n")).is_err());
+        assert!(roundtrip_check(&maze("#####\n#S..#\n#####\n")).is_err());
+    }
+
+    #[test]
+    fn a_truncated_last_bundle_line_is_skipped_and_repaired() {
+        let filename = &temp_path("truncated.jsonl");
+        let _ = std::fs::remove_file(filename);
+        let mazes: Vec<Maze> = (0..3).map(generated).collect();
+        let append = |maze: &Maze| {
+            let solution = solve_maze(maze).unwrap();
+            let options = JsonOptions::default();
+            append_to_bundle(maze.cols, maze.rows, maze, &solution, filename, &options).unwrap();
+        };
+        for maze in &mazes[..2] {
+            append(maze);
+        }
+        let whole = std::fs::read(filename).unwrap();
+        append(&mazes[2]);
+        // Cut the third line off halfway, as an interrupted write would.
+        let full = std::fs::metadata(filename).unwrap().len();
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(filename)
+            .unwrap();
+        file.set_len((whole.len() as u64 + full) / 2).unwrap();
+
+        let bundle = read_bundle(filename).unwrap();
+        assert!(bundle.truncated);
+        assert_eq!(bundle.mazes.len(), 2);
+        assert!(bundle.mazes[1].eq_ignoring_solution(&mazes[1]));
+
+        assert!(repair_bundle(filename).unwrap().truncated);
+        assert_eq!(std::fs::read(filename).unwrap(), whole);
+        append(&mazes[2]);
+        let bundle = read_bundle(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        assert!(!bundle.truncated);
+        assert_eq!(bundle.mazes.len(), 3);
+    }
+
+    #[test]
+    fn one_cell_wide_mazes_run_through_the_pipeline() {
+        use solver::PathFinder;
+
+        for (text, rows, cols) in [("S...G\n", 1, 5), ("S\n.\n.\n.\nG\n", 5, 1)] {
+            let maze = maze(text);
+            assert_eq!((maze.rows, maze.cols), (rows, cols));
+            let solution = solve_maze(&maze).unwrap();
+            assert_eq!(solution.len(), 5);
+            assert!(solver::validate_solution(&maze, &solution).is_ok());
+            assert_eq!(solver::Bfs.find_path(&maze).unwrap().length, 5);
+
+            let text = maze_json_string(
+                maze.cols,
+                maze.rows,
+                &maze,
+                &solution,
+                &JsonOptions::default(),
+            );
+            let json: serde_json::Value = serde_json::from_str(&text.unwrap()).unwrap();
+            assert_eq!(
+                (json["width"].as_u64(), json["height"].as_u64()),
+                (Some(cols as u64), Some(rows as u64))
+            );
+            assert_eq!(json["maze"].as_array().unwrap().len(), 5);
+            let last = &json["solution"][4];
+            assert_eq!(
+                (last["x"].as_u64(), last["y"].as_u64()),
+                (Some(cols as u64 - 1), Some(rows as u64 - 1))
+            );
+            assert_eq!(json["goal"], *last);
+        }
+    }
+}
@@ -1,220 +1,683 @@
+mod cli;
+
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    fs::File,
-    io::{BufRead, BufReader, Error, Write},
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    process,
+    sync::atomic::{AtomicBool, Ordering},
+    sync::mpsc::{self, RecvTimeoutError},
+    sync::Arc,
+    thread,
+    time::Duration,
 };
 
-use knossos::maze::*;
-use serde::Serialize;
-
-const SIZE: usize = 10;
+use clap::Parser;
+use log::{error, info, warn};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
 
-struct Maze {
-    rows: usize,
-    cols: usize,
-    data: Vec<Vec<char>>,
-}
+use cli::{Args, OutputFormat};
+use labyrinthium_generator::{
+    animation::{bfs_layers, create_gif_file, GifOptions},
+    append_to_bundle,
+    batch::{read_seed_file, unique_seeds},
+    binary::create_binary_file,
+    checksum::write_checksum,
+    connectivity::raise_connectivity,
+    create_grid_json_file, create_json_file, create_solution_json_file,
+    diff::diff_mazes,
+    ensemble::derive_variant,
+    error::MazeError,
+    generate_maze,
+    manifest::{write_manifest, ManifestEntry, ManifestRecord},
+    mask::{carve_masked_recorded, load_mask},
+    profile::{timed, write_profile, Profile},
+    read_maze_from_file_with_mode,
+    render::{add_rulers, ensure_ascii, render, render_ascii},
+    render_maze, repair_bundle,
+    retry::write_file,
+    rle::create_rle_file,
+    solver::{
+        check_endpoints, compare_solvers, dfs, solve_with_goal_selection, validate_solution,
+        Dijkstra, PathFinder, Solution,
+    },
+    stats::{compute_stats, corridor_width_profile, count_shortest_paths, loop_count, Stats},
+    summary::{summarize, write_summary},
+    template::TemplateFields,
+    tiled::create_tiled_file,
+    transform::{
+        add_loops, adjust_dead_ends, assign_weights, connect_endpoints, open_to_count, open_walls,
+        pad_to, place_goal,
+    },
+    JsonOptions, Maze, ReadMode,
+};
 
-#[derive(Serialize)]
-struct Cell {
-    x: usize,
-    y: usize,
-    #[serde(rename = "type")]
-    cell_type: u8,
-}
+fn main() {
+    let mut args = Args::parse();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
-#[derive(Serialize)]
-struct MazeJson {
-    width: usize,
-    height: usize,
-    start: Position,
-    goal: Position,
-    maze: Vec<Cell>,
-    solution: Vec<Position>,
-}
+    if let Some(files) = &args.diff {
+        process::exit(match run_diff(&files[0], &files[1], args.read_mode()) {
+            Ok(0) => 0,
+            Ok(_) => 1,
+            Err(e) => {
+                error!("{}", e);
+                2
+            }
+        });
+    }
+    if args.legacy_coords {
+        info!("--legacy-coords has no effect: the coordinate order never changed.");
+    }
+    let base_seed = *args.seed.get_or_insert_with(rand::random);
 
-#[derive(Serialize)]
-struct Position {
-    x: usize,
-    y: usize,
-}
+    let seeds = if let Some(filename) = &args.seed_file {
+        match read_seed_file(filename) {
+            Ok(seeds) => seeds,
+            Err(e) => {
+                error!("Error reading seed file: {}", e);
+                process::exit(1);
+            }
+        }
+    } else if args.repeat_until_unique {
+        let max_attempts = args.max_attempts.unwrap_or(args.count * 10);
+        match unique_seeds(base_seed, args.count, max_attempts, |seed| {
+            build_maze(&args, seed)
+        }) {
+            Ok(batch) => {
+                info!("Skipped {} duplicate mazes.", batch.collisions);
+                batch.seeds
+            }
+            Err(e) => {
+                error!("{}", e);
+                process::exit(1);
+            }
+        }
+    } else {
+        (0..args.count as u64)
+            .map(|i| base_seed.wrapping_add(i))
+            .collect()
+    };
 
-fn main() {
-    let maze = OrthogonalMazeBuilder::new()
-        .height(SIZE)
-        .width(SIZE)
-        .algorithm(Box::new(GrowingTree::new(Method::Random)))
-        .build();
-
-    match maze.save("output/maze.txt", GameMap::new().span(1).with_start_goal()) {
-        Ok(_) => {
-            if let Ok(maze) = read_maze_from_file("output/maze.txt") {
-                println!("Original maze:");
-                for row in &maze.data {
-                    for &cell in row {
-                        print!("{}", cell);
-                    }
-                    println!();
+    if let (Some(filename), false) = (&args.bundle, args.append) {
+        if let Err(err) = write_file(filename, &[], &args.retry_policy()) {
+            error!("Error creating bundle file: {}", err);
+            process::exit(1);
+        }
+    }
+    if let (Some(filename), true) = (&args.bundle, args.append) {
+        if Path::new(filename).exists() {
+            match repair_bundle(filename) {
+                Ok(bundle) if bundle.truncated => warn!(
+                    "Dropped a truncated last line from the bundle; {} mazes recovered.",
+                    bundle.mazes.len()
+                ),
+                Ok(_) => {}
+                Err(err) => {
+                    error!("Error reading bundle file: {}", err);
+                    process::exit(1);
                 }
+            }
+        }
+    }
+
+    let args = Arc::new(args);
+    let mut stats = Vec::new();
+    let mut records = Vec::with_capacity(seeds.len());
+    let mut failures = 0;
+    let mut profile = Profile::default();
+    for (index, &seed) in seeds.iter().enumerate() {
+        match run_maze_isolated(&args, seed, index, seeds.len()) {
+            Ok((entry, maze_stats, maze_profile)) => {
+                records.extend(entry.map(ManifestRecord::Generated));
+                stats.push(maze_stats);
+                profile.add(&maze_profile);
+            }
+            Err(reason) => {
+                error!("Maze {} (seed {}) failed: {}", index, seed, reason);
+                records.push(ManifestRecord::Failed {
+                    seed,
+                    error: reason,
+                });
+                failures += 1;
+            }
+        }
+    }
+
+    if let Some(filename) = &args.manifest {
+        match write_manifest(&records, filename, &args.retry_policy()) {
+            Ok(bytes) => {
+                info!("Manifest file created successfully.");
+                checksum(&args, filename, &bytes);
+            }
+            Err(err) => error!("Error creating manifest file: {}", err),
+        }
+    }
+
+    if let Some(filename) = &args.profile {
+        match write_profile(&profile, filename, &args.retry_policy()) {
+            Ok(bytes) => {
+                info!("Profile file created successfully.");
+                checksum(&args, filename, &bytes);
+            }
+            Err(err) => error!("Error creating profile file: {}", err),
+        }
+    }
 
-                if let Some(path) = solve_maze(&maze) {
-                    if let Err(err) = create_json_file(
-                        maze.cols,
-                        maze.rows,
-                        &maze,
-                        &path,
-                        format!("output/maze-{}x{}.json", SIZE, SIZE).as_str(),
-                    ) {
-                        println!("Error creating JSON file: {:?}", err);
-                    } else {
-                        println!("JSON file created successfully.");
-                    }
-                } else {
-                    println!("No path found.");
+    if args.summary || args.summary_json.is_some() {
+        let summary = summarize(&stats);
+        if args.summary {
+            println!("{}", summary);
+        }
+        if let Some(filename) = &args.summary_json {
+            match write_summary(&summary, filename, &args.retry_policy()) {
+                Ok(bytes) => {
+                    info!("Summary file created successfully.");
+                    checksum(&args, filename, &bytes);
                 }
-            } else {
-                println!("Error reading maze file.");
+                Err(err) => error!("Error creating summary file: {}", err),
             }
         }
-        Err(e) => println!("Error: {}", e),
     }
+
+    // The bundle is appended to maze by maze and may hold lines from earlier runs, so its
+    // checksum covers the whole file as it ends up on disk.
+    if let Some(filename) = args.bundle.as_ref().filter(|_| args.checksum) {
+        match fs::read(filename) {
+            Ok(bytes) => checksum(&args, filename, &bytes),
+            Err(err) => error!("Error creating checksum for {}: {}", filename, err),
+        }
+    }
+
+    if failures > 0 {
+        error!("{} of {} mazes failed.", failures, seeds.len());
+        process::exit(1);
+    }
+}
+
+/// The manifest entry (none in stats-only mode), stats and phase timings of one maze,
+/// or a description of why it failed.
+type MazeResult = Result<(Option<ManifestEntry>, Stats, Profile), String>;
+type BuildSteps = Vec<(usize, usize)>;
+
+/// Generates, solves and writes one maze. Once `cancel` is set nothing more is printed
+/// or written, so an abandoned maze cannot interfere with the ones after it.
+fn run_maze(args: &Args, seed: u64, index: usize, count: usize, cancel: &AtomicBool) -> MazeResult {
+    let mut profile = Profile::default();
+    let seed = timed(&mut profile.generate, || {
+        seed_within_path_bounds(args, seed)
+    })
+    .map_err(|e| e.to_string())?;
+    let algorithm = args.algorithm(seed);
+
+    if args.stats_only {
+        let maze = timed(&mut profile.generate, || {
+            let mut maze =
+                build_maze(args, seed).map_err(|e| format!("Error reading maze: {}", e))?;
+            transform(args, &mut maze, seed).map_err(|e| e.to_string())?;
+            Ok::<_, String>(maze)
+        })?;
+        let path = timed(&mut profile.solve, || solve(&maze, args))
+            .ok_or("No path found.")?
+            .into_path();
+        let mut maze_stats = compute_stats(&maze, &path);
+        if args.count_solutions {
+            maze_stats.shortest_path_count = Some(count_shortest_paths(&maze));
+        }
+        if args.corridor_widths {
+            maze_stats.corridor_width_profile = Some(corridor_width_profile(&maze, &path));
+        }
+        check_cancelled(cancel)?;
+        if args.compare_solvers {
+            print_solver_comparison(&maze, args);
+        }
+        println!("{}", maze_stats);
+        return Ok((None, maze_stats, profile));
+    }
+
+    let built = if args.ensemble.is_some() {
+        timed(&mut profile.generate, || build_maze(args, seed)).map(|maze| (maze, None))
+    } else if args.mask.is_some() || args.record_build {
+        timed(&mut profile.generate, || build_recorded(args, seed))
+    } else {
+        let generated = timed(&mut profile.generate, || {
+            generate_maze(algorithm, args.width, args.height, seed)
+        });
+        timed(&mut profile.read, || render_maze(&generated, seed)).map(|maze| (maze, None))
+    };
+    let (mut maze, build_steps) = built.map_err(|e| format!("Error reading maze: {}", e))?;
+    if args.emit_txt {
+        let txt_filename = output_path(&format!("{}/maze.txt", args.out_dir), index, count);
+        let text = maze.to_string();
+        if args.ascii_only {
+            ensure_ascii(&text).map_err(|e| format!("Error writing maze text file: {}", e))?;
+        }
+        check_cancelled(cancel)?;
+        write_file(&txt_filename, text.as_bytes(), &args.retry_policy())
+            .map_err(|e| format!("Error writing maze text file: {}", e))?;
+        check_cancelled(cancel)?;
+        checksum(args, &txt_filename, text.as_bytes());
+    }
+    timed(&mut profile.generate, || transform(args, &mut maze, seed)).map_err(|e| e.to_string())?;
+    let solution = timed(&mut profile.solve, || solve(&maze, args));
+    let overlay = solution
+        .as_ref()
+        .map(Solution::path)
+        .filter(|_| args.show_solution);
+    let rendered = if args.ascii_only {
+        render_ascii(&maze, args.render, overlay).map_err(|e| e.to_string())?
+    } else {
+        render(&maze, args.render, overlay)
+    };
+    let rendered = if args.pretty_ascii {
+        add_rulers(&rendered, args.render, maze.cols)
+    } else {
+        rendered
+    };
+    check_cancelled(cancel)?;
+    println!("Original maze:");
+    print!("{}", rendered);
+    if args.compare_solvers {
+        print_solver_comparison(&maze, args);
+    }
+    let solution = solution.ok_or("No path found.")?;
+    validate_solution(&maze, solution.path()).map_err(|e| format!("Invalid solution: {}", e))?;
+    let maze_stats = compute_stats(&maze, solution.path());
+    let mut json_options = args.json_options(algorithm);
+    json_options.solver = Some(solution.algorithm);
+    json_options.expanded_nodes = solution.expanded_nodes;
+    json_options.build_steps = build_steps;
+    let file = timed(&mut profile.serialize, || {
+        write_outputs(
+            args,
+            &maze,
+            solution.path(),
+            &json_options,
+            Slot { seed, index, count },
+            cancel,
+        )
+    })
+    .ok_or("No output file was written.")?;
+    let entry = ManifestEntry {
+        file,
+        seed,
+        algorithm,
+        width: maze.cols,
+        height: maze.rows,
+        content_hash: format!("{:016x}", maze.content_hash()),
+    };
+    Ok((Some(entry), maze_stats, profile))
 }
 
-fn read_maze_from_file(filename: &str) -> Result<Maze, Error> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+/// Runs [`run_maze`], turning a panic into a failure of just this maze. With
+/// `--maze-timeout` the maze runs on its own thread and is given up on once the timeout
+/// passes; it keeps computing in the background until the process exits, but is
+/// cancelled so it writes nothing more.
+fn run_maze_isolated(args: &Arc<Args>, seed: u64, index: usize, count: usize) -> MazeResult {
+    let Some(timeout) = args.maze_timeout else {
+        return run_maze_caught(args, seed, index, count, &AtomicBool::new(false));
+    };
+    let (sender, receiver) = mpsc::channel();
+    let thread_args = Arc::clone(args);
+    let cancel = Arc::new(AtomicBool::new(false));
+    let thread_cancel = Arc::clone(&cancel);
+    thread::spawn(move || {
+        let _ = sender.send(run_maze_caught(
+            &thread_args,
+            seed,
+            index,
+            count,
+            &thread_cancel,
+        ));
+    });
+    match receiver.recv_timeout(Duration::from_secs_f64(timeout)) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            cancel.store(true, Ordering::Relaxed);
+            Err(format!("Timed out after {} seconds.", timeout))
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            Err("Maze thread stopped without a result.".to_string())
+        }
+    }
+}
 
-    let mut rows = 0;
-    let mut cols = 0;
-    let mut data = Vec::new();
+fn run_maze_caught(
+    args: &Args,
+    seed: u64,
+    index: usize,
+    count: usize,
+    cancel: &AtomicBool,
+) -> MazeResult {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        run_maze(args, seed, index, count, cancel)
+    }))
+    .unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown panic");
+        Err(format!("Panicked: {}", message))
+    })
+}
 
-    for line in reader.lines() {
-        let line = line?;
-        let chars: Vec<char> = line.chars().collect();
-        cols = chars.len();
-        data.push(chars);
-        rows += 1;
+fn check_cancelled(cancel: &AtomicBool) -> Result<(), String> {
+    if cancel.load(Ordering::Relaxed) {
+        Err(MazeError::Cancelled.to_string())
+    } else {
+        Ok(())
     }
+}
 
-    Ok(Maze { rows, cols, data })
+/// A maze's seed and place in the batch, which its file names are made from.
+struct Slot {
+    seed: u64,
+    index: usize,
+    count: usize,
 }
 
-fn solve_maze(maze: &Maze) -> Option<Vec<(usize, usize)>> {
-    let mut visited = HashSet::new();
-    let mut stack = VecDeque::new();
-    let mut parents = HashMap::new();
+fn write_outputs(
+    args: &Args,
+    maze: &Maze,
+    path: &[(usize, usize)],
+    json_options: &JsonOptions,
+    Slot { seed, index, count }: Slot,
+    cancel: &AtomicBool,
+) -> Option<String> {
+    let retry = args.retry_policy();
+    let cancelled = || cancel.load(Ordering::Relaxed);
 
-    let start = find_start(&maze);
-    stack.push_back(start);
-    visited.insert(start);
+    let format = args.output_format();
+    let filename = match &args.output_template {
+        Some(template) => {
+            let name = template.render(&TemplateFields {
+                width: maze.cols,
+                height: maze.rows,
+                seed,
+                index,
+                ext: format.extension(),
+            });
+            let path = format!("{}/{}", args.out_dir, name);
+            if template.is_unique_per_maze() {
+                path
+            } else {
+                output_path(&path, index, count)
+            }
+        }
+        None => output_path(
+            &format!(
+                "{}/maze-{}x{}.{}",
+                args.out_dir,
+                maze.cols,
+                maze.rows,
+                format.extension()
+            ),
+            index,
+            count,
+        ),
+    };
+    if cancelled() {
+        return None;
+    }
+    let result = match format {
+        OutputFormat::Json => create_json_file(
+            maze.cols,
+            maze.rows,
+            maze,
+            path,
+            &filename,
+            json_options,
+            &retry,
+        ),
+        OutputFormat::Rle => create_rle_file(maze, path, &filename, &retry),
+        OutputFormat::Tiled => create_tiled_file(maze, &filename, &retry).map_err(MazeError::from),
+        OutputFormat::Binary => create_binary_file(maze, &filename, &retry),
+        OutputFormat::Solution => {
+            create_solution_json_file(maze, path, &filename, &retry).map_err(MazeError::from)
+        }
+        OutputFormat::Grid => create_grid_json_file(maze, path, &filename, &retry),
+    };
+    if let Some(bundle) = args.bundle.as_ref().filter(|_| !cancelled()) {
+        match append_to_bundle(maze.cols, maze.rows, maze, path, bundle, json_options) {
+            Ok(_) => info!("Maze appended to bundle."),
+            Err(err) => error!("Error appending to bundle: {}", err),
+        }
+    }
 
-    while let Some((row, col)) = stack.pop_back() {
-        if maze.data[row][col] == 'G' {
-            return Some(construct_path((row, col), &parents));
+    let written = match result {
+        Ok(bytes) => {
+            info!("{} file created successfully.", format.label());
+            if !cancelled() {
+                checksum(args, &filename, &bytes);
+            }
+            Some(filename)
         }
+        Err(err) => {
+            error!("Error creating {} file: {}", format.label(), err);
+            None
+        }
+    };
 
-        for (dr, dc) in &[(-1, 0), (1, 0), (0, -1), (0, 1)] {
-            let (new_row, new_col) = (row as i32 + dr, col as i32 + dc);
-            if new_row >= 0
-                && new_row < maze.rows as i32
-                && new_col >= 0
-                && new_col < maze.cols as i32
-                && maze.data[new_row as usize][new_col as usize] != '#'
-                && !visited.contains(&(new_row as usize, new_col as usize))
-            {
-                stack.push_back((new_row as usize, new_col as usize));
-                visited.insert((new_row as usize, new_col as usize));
-                parents.insert((new_row as usize, new_col as usize), (row, col));
+    if let Some(filename) = args.gif.as_ref().filter(|_| !cancelled()) {
+        let options = GifOptions {
+            frame_delay_ms: args.gif_delay,
+            scale: args.gif_scale,
+        };
+        let filename = output_path(filename, index, count);
+        match create_gif_file(
+            maze,
+            &bfs_layers(maze),
+            Some(path),
+            &options,
+            &filename,
+            &retry,
+        ) {
+            Ok(bytes) => {
+                info!("GIF file created successfully.");
+                if !cancelled() {
+                    checksum(args, &filename, &bytes);
+                }
             }
+            Err(err) => error!("Error creating GIF file: {}", err),
         }
     }
 
-    None
+    written
 }
 
-fn find_start(maze: &Maze) -> (usize, usize) {
-    for (i, row) in maze.data.iter().enumerate() {
-        for (j, &c) in row.iter().enumerate() {
-            if c == 'S' {
-                return (i, j);
+/// Returns `seed` if its maze satisfies `--min-path` and `--max-path`, otherwise the
+/// first seed drawn from a stream seeded with it whose maze does. The manifest records
+/// the seed that was used, so the batch still replays exactly.
+fn seed_within_path_bounds(args: &Args, seed: u64) -> Result<u64, MazeError> {
+    if args.min_path.is_none() && args.max_path.is_none() {
+        return Ok(seed);
+    }
+    let mut candidates = StdRng::seed_from_u64(seed);
+    let mut candidate = seed;
+    for _ in 0..args.path_attempts {
+        let mut maze = build_maze(args, candidate)?;
+        transform(args, &mut maze, candidate)?;
+        if let Some(solution) = solve(&maze, args) {
+            if args.min_path.is_none_or(|min| solution.length >= min)
+                && args.max_path.is_none_or(|max| solution.length <= max)
+            {
+                return Ok(candidate);
             }
         }
+        candidate = candidates.next_u64();
     }
-    panic!("No starting point 'S' found in the maze.");
+    Err(MazeError::PathBoundsUnmet {
+        attempts: args.path_attempts,
+    })
 }
 
-fn construct_path(
-    goal: (usize, usize),
-    parents: &HashMap<(usize, usize), (usize, usize)>,
-) -> Vec<(usize, usize)> {
-    let mut path = Vec::new();
-    let mut current = goal;
-    while let Some(&parent) = parents.get(&current) {
-        path.push(current);
-        current = parent;
+/// Writes the `--checksum` sidecar for `bytes`, just written to `filename`, logging rather
+/// than failing on error.
+fn checksum(args: &Args, filename: &str, bytes: &[u8]) {
+    if args.checksum {
+        if let Err(err) = write_checksum(filename, bytes, &args.retry_policy()) {
+            error!("Error creating checksum for {}: {}", filename, err);
+        }
     }
-    path.push(current);
-    path.reverse();
-    path
 }
 
-fn create_json_file(
-    width: usize,
-    height: usize,
-    maze: &Maze,
-    solution: &Vec<(usize, usize)>,
-    filename: &str,
-) -> Result<(), std::io::Error> {
-    let maze_cells = maze
-        .data
-        .iter()
-        .enumerate()
-        .flat_map(|(y, row)| {
-            row.iter().enumerate().map(move |(x, &cell)| {
-                let cell_type = match cell {
-                    'S' => 0,
-                    'G' => 1,
-                    '.' => 2,
-                    '#' => 3,
-                    _ => panic!("Unknown cell type"),
-                };
-                Cell { x, y, cell_type }
-            })
-        })
-        .collect::<Vec<_>>();
-
-    let solution_cells = solution
-        .iter()
-        .map(|&(y, x)| Position { x, y })
-        .collect::<Vec<_>>();
-
-    let start: (usize, usize) = find_start(maze);
-    let goal: (usize, usize) = maze
-        .data
-        .iter()
-        .enumerate()
-        .find_map(|(y, row)| row.iter().position(|&c| c == 'G').map(|x| (x, y)))
-        .unwrap();
-
-    let maze_json = MazeJson {
-        width,
-        height,
-        start: Position {
-            x: start.1,
-            y: start.0,
-        },
-        goal: Position {
-            x: goal.0,
-            y: goal.1,
-        },
-        maze: maze_cells,
-        solution: solution_cells,
+/// Generates the maze for `seed`, carving inside `--mask` when one is given. With
+/// `--ensemble` the maze is a variant of the base seed's maze instead.
+fn build_maze(args: &Args, seed: u64) -> Result<Maze, MazeError> {
+    match (args.ensemble, args.seed) {
+        (Some(radius), Some(base_seed)) => Ok(derive_variant(
+            &build_base_maze(args, base_seed)?,
+            radius,
+            seed,
+        )),
+        _ => build_base_maze(args, seed),
+    }
+}
+
+fn build_base_maze(args: &Args, seed: u64) -> Result<Maze, MazeError> {
+    build_recorded(args, seed).map(|(maze, _)| maze)
+}
+
+/// Builds the maze as [`build_base_maze`] does, plus its carve steps with
+/// `--record-build`, which replaces knossos with the built-in backtracker.
+fn build_recorded(args: &Args, seed: u64) -> Result<(Maze, Option<BuildSteps>), MazeError> {
+    let (cols, rows) = (2 * args.width + 1, 2 * args.height + 1);
+    let allowed = match &args.mask {
+        Some(filename) => load_mask(filename, cols, rows)?,
+        None if args.record_build => vec![vec![true; cols]; rows],
+        None => {
+            let maze = render_maze(
+                &generate_maze(args.algorithm(seed), args.width, args.height, seed),
+                seed,
+            )?;
+            return Ok((maze, None));
+        }
     };
+    let (maze, steps) = carve_masked_recorded(&allowed, &mut StdRng::seed_from_u64(seed))?;
+    Ok((maze, args.record_build.then_some(steps)))
+}
+
+fn run_diff(left: &str, right: &str, mode: ReadMode) -> Result<usize, MazeError> {
+    let diffs = diff_mazes(
+        &read_maze_from_file_with_mode(left, mode)?,
+        &read_maze_from_file_with_mode(right, mode)?,
+    )?;
+    for diff in &diffs {
+        println!(
+            "row {}, col {}: '{}' != '{}'",
+            diff.row, diff.col, diff.left, diff.right
+        );
+    }
+    println!("{} cells differ.", diffs.len());
+    Ok(diffs.len())
+}
+
+fn transform(args: &Args, maze: &mut Maze, seed: u64) -> Result<(), MazeError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    if let Some(ratio) = args.openness {
+        open_walls(maze, ratio, &mut rng);
+    }
+    if let Some(loops) = args.loops {
+        let before = loop_count(maze);
+        let added = add_loops(maze, loops, &mut rng);
+        if added < loops {
+            warn!("Only {} of {} loops could be added.", added, loops);
+        }
+        if loop_count(maze) != before + added {
+            error!("Loop count check failed after adding {} loops.", added);
+        }
+    }
+    if let Some(target) = args.dead_ends {
+        let achieved = adjust_dead_ends(maze, target, &mut rng);
+        if achieved != target {
+            warn!("Reached {} dead ends instead of {}.", achieved, target);
+        }
+    }
+    if let Some(k) = args.connectivity {
+        let opened = raise_connectivity(maze, k as usize, &mut rng)?;
+        info!("Opened {} walls for {}-connectivity.", opened, k);
+    }
+    if args.invert {
+        *maze = maze.invert()?;
+    }
+    if let Some(target) = args.open_cells {
+        let achieved = open_to_count(maze, target, args.open_cells_tolerance, &mut rng)?;
+        info!("Maze has {} open cells.", achieved);
+    }
+    if let Some(placement) = args.goal_placement {
+        let (row, col) = place_goal(maze, placement, &mut rng)?;
+        info!("Placed the goal at row {}, column {}.", row, col);
+    }
+    if let Some(max_weight) = args.terrain {
+        assign_weights(maze, max_weight, &mut rng);
+    }
+    if let Some((width, height)) = args.pad_to {
+        *maze = pad_to(maze, width, height)?;
+    }
+    if let Some(factor) = args.upscale {
+        *maze = maze.upscale(factor as usize);
+    }
+    if args.fix {
+        let carved = connect_endpoints(maze)?;
+        if carved > 0 {
+            info!("Carved {} walls to connect the start and goals.", carved);
+        }
+    }
+    let density = maze.open_density();
+    if density < args.min_open_density || density > args.max_open_density {
+        warn!(
+            "Open density {:.4} is outside the expected range {}..={}.",
+            density, args.min_open_density, args.max_open_density
+        );
+    }
+    check_endpoints(maze)
+}
 
-    let json_string = serde_json::to_string_pretty(&maze_json)?;
+fn output_path(base: &str, index: usize, count: usize) -> String {
+    if count <= 1 {
+        return base.to_string();
+    }
+    match base.rfind('.') {
+        Some(dot) => format!("{}-{}{}", &base[..dot], index, &base[dot..]),
+        None => format!("{}-{}", base, index),
+    }
+}
 
-    let mut file = File::create(filename)?;
-    file.write_all(json_string.as_bytes())?;
+/// Prints one line per solver with its path length, expanded cells and whether the path
+/// is as short as any other solver's.
+fn print_solver_comparison(maze: &Maze, args: &Args) {
+    let solutions = compare_solvers(maze, &args.dfs_order.unwrap_or_default());
+    let shortest = solutions.iter().map(|solution| solution.length).min();
+    println!(
+        "{:<18} {:>6} {:>8}  shortest",
+        "solver", "length", "expanded"
+    );
+    for solution in &solutions {
+        let expanded = solution
+            .expanded_nodes
+            .map_or("-".to_string(), |expanded| expanded.to_string());
+        let optimal = if Some(solution.length) == shortest {
+            "yes"
+        } else {
+            "no"
+        };
+        println!(
+            "{:<18} {:>6} {:>8}  {}",
+            solution.algorithm.name(),
+            solution.length,
+            expanded,
+            optimal
+        );
+    }
+}
 
-    Ok(())
+fn solve(maze: &Maze, args: &Args) -> Option<Solution> {
+    if args.weighted {
+        return Dijkstra.find_path(maze);
+    }
+    match args.goal_selection {
+        Some(selection) => solve_with_goal_selection(maze, selection),
+        None => dfs(maze, &args.dfs_order.unwrap_or_default()),
+    }
 }
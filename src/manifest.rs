@@ -0,0 +1,37 @@
+use serde::Serialize;
+
+use crate::{
+    algorithm::GenerationAlgorithm,
+    retry::{write_file, RetryPolicy},
+};
+
+/// Provenance record of one generated maze.
+#[derive(Serialize)]
+pub struct ManifestEntry {
+    pub file: String,
+    pub seed: u64,
+    pub algorithm: GenerationAlgorithm,
+    pub width: usize,
+    pub height: usize,
+    /// Hex-encoded [`crate::Maze::content_hash`].
+    pub content_hash: String,
+}
+
+/// A manifest line: either a maze that was written or a seed that failed.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum ManifestRecord {
+    Generated(ManifestEntry),
+    Failed { seed: u64, error: String },
+}
+
+/// Writes the manifest as pretty JSON and returns the bytes written.
+pub fn write_manifest(
+    entries: &[ManifestRecord],
+    filename: &str,
+    retry: &RetryPolicy,
+) -> Result<Vec<u8>, std::io::Error> {
+    let json_string = serde_json::to_string_pretty(entries)?;
+    write_file(filename, json_string.as_bytes(), retry)?;
+    Ok(json_string.into_bytes())
+}
@@ -0,0 +1,185 @@
+use std::collections::{HashSet, VecDeque};
+
+use image::imageops::{self, FilterType};
+use rand::{rngs::StdRng, seq::IndexedRandom, Rng, SeedableRng};
+
+use crate::{error::MazeError, Maze};
+
+/// Loads a black-and-white image and scales it to `cols` x `rows`. A cell is allowed
+/// when its pixel is light; dark pixels mark cells that must stay wall.
+pub fn load_mask(filename: &str, cols: usize, rows: usize) -> Result<Vec<Vec<bool>>, MazeError> {
+    let image = image::open(filename)?.to_luma8();
+    let scaled = imageops::resize(&image, cols as u32, rows as u32, FilterType::Nearest);
+    Ok((0..rows)
+        .map(|row| {
+            (0..cols)
+                .map(|col| scaled.get_pixel(col as u32, row as u32).0[0] >= 128)
+                .collect()
+        })
+        .collect())
+}
+
+/// Carves a perfect maze with a randomized depth-first backtracker, using only cells the
+/// mask allows. Rooms sit on odd coordinates as in the generated text mazes. The start
+/// goes in the largest connected region and the goal at the room farthest from it.
+pub fn carve_masked(allowed: &[Vec<bool>], seed: u64) -> Result<Maze, MazeError> {
+    carve_masked_with_rng(allowed, &mut StdRng::seed_from_u64(seed))
+}
+
+/// Like [`carve_masked`], but draws every choice from `rng`.
+pub fn carve_masked_with_rng<R: Rng + ?Sized>(
+    allowed: &[Vec<bool>],
+    rng: &mut R,
+) -> Result<Maze, MazeError> {
+    carve_masked_recorded(allowed, rng).map(|(maze, _)| maze)
+}
+
+/// Like [`carve_masked_with_rng`], but also returns every cell carved, in order: each
+/// region's first room, then a wall and the room behind it per step. Replaying them
+/// with [`replay_build_steps`] gives the carved grid without the start and goal.
+pub fn carve_masked_recorded<R: Rng + ?Sized>(
+    allowed: &[Vec<bool>],
+    rng: &mut R,
+) -> Result<(Maze, Vec<(usize, usize)>), MazeError> {
+    let rows = allowed.len();
+    let cols = allowed.first().map_or(0, Vec::len);
+    let mut data = vec![vec!['#'; cols]; rows];
+    let room = |(row, col): (usize, usize)| row % 2 == 1 && col % 2 == 1 && allowed[row][col];
+
+    let mut visited = HashSet::new();
+    let mut steps = Vec::new();
+    let mut largest: Vec<(usize, usize)> = Vec::new();
+    for row in (1..rows).step_by(2) {
+        for col in (1..cols).step_by(2) {
+            if !room((row, col)) || visited.contains(&(row, col)) {
+                continue;
+            }
+            let mut region = vec![(row, col)];
+            let mut stack = vec![(row, col)];
+            visited.insert((row, col));
+            data[row][col] = '.';
+            steps.push((row, col));
+
+            while let Some(&(r, c)) = stack.last() {
+                let mut next = Vec::with_capacity(4);
+                for (dr, dc) in &[(-2, 0), (2, 0), (0, -2), (0, 2)] {
+                    let (new_row, new_col) = (r as i32 + dr, c as i32 + dc);
+                    if new_row < 0 || new_col < 0 {
+                        continue;
+                    }
+                    let (new_row, new_col) = (new_row as usize, new_col as usize);
+                    let (wall_row, wall_col) = ((r + new_row) / 2, (c + new_col) / 2);
+                    if new_row < rows
+                        && new_col < cols
+                        && room((new_row, new_col))
+                        && allowed[wall_row][wall_col]
+                        && !visited.contains(&(new_row, new_col))
+                    {
+                        next.push(((new_row, new_col), (wall_row, wall_col)));
+                    }
+                }
+
+                match next.choose(rng) {
+                    Some(&(cell, wall)) => {
+                        data[wall.0][wall.1] = '.';
+                        data[cell.0][cell.1] = '.';
+                        steps.extend([wall, cell]);
+                        visited.insert(cell);
+                        region.push(cell);
+                        stack.push(cell);
+                    }
+                    None => {
+                        stack.pop();
+                    }
+                }
+            }
+
+            if region.len() > largest.len() {
+                largest = region;
+            }
+        }
+    }
+
+    if largest.len() < 2 {
+        return Err(MazeError::EmptyMask);
+    }
+    let mut maze = Maze { rows, cols, data };
+    let start = largest[0];
+    let goal = farthest_cell(&maze, start);
+    maze.data[start.0][start.1] = 'S';
+    maze.data[goal.0][goal.1] = 'G';
+
+    Ok((maze, steps))
+}
+
+/// Opens the recorded `steps` in order on a `rows` x `cols` grid of walls.
+pub fn replay_build_steps(rows: usize, cols: usize, steps: &[(usize, usize)]) -> Maze {
+    let mut data = vec![vec!['#'; cols]; rows];
+    for &(row, col) in steps {
+        data[row][col] = '.';
+    }
+    Maze { rows, cols, data }
+}
+
+fn farthest_cell(maze: &Maze, start: (usize, usize)) -> (usize, usize) {
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    let mut last = start;
+
+    while let Some(cell) = queue.pop_front() {
+        last = cell;
+        for (next, _) in maze.open_neighbors_dir(cell) {
+            if visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{GrayImage, Luma};
+
+    use super::*;
+    use crate::{solve_maze, test_support::temp_path};
+
+    #[test]
+    fn forbidden_cells_stay_walls() {
+        // A white disc on black, one pixel per cell.
+        let image = GrayImage::from_fn(21, 21, |x, y| {
+            let (dx, dy) = (x as i32 - 10, y as i32 - 10);
+            Luma([if dx * dx + dy * dy <= 81 { 255 } else { 0 }])
+        });
+        let filename = &temp_path("mask.png");
+        image.save(filename).unwrap();
+        let allowed = load_mask(filename, 21, 21).unwrap();
+        std::fs::remove_file(filename).unwrap();
+
+        let maze = carve_masked(&allowed, 131).unwrap();
+        for (row, line) in maze.data.iter().enumerate() {
+            for (col, &cell) in line.iter().enumerate() {
+                if !allowed[row][col] {
+                    assert_eq!(cell, '#', "forbidden cell ({}, {}) is open", row, col);
+                }
+            }
+        }
+        assert!(!allowed[0][0] && allowed[10][10]);
+        assert!(solve_maze(&maze).is_some());
+    }
+
+    #[test]
+    fn replaying_the_build_steps_reconstructs_the_maze() {
+        let allowed = vec![vec![true; 21]; 15];
+        let (maze, steps) =
+            carve_masked_recorded(&allowed, &mut StdRng::seed_from_u64(172)).unwrap();
+        let replayed = replay_build_steps(maze.rows, maze.cols, &steps);
+        let floor = |cell: char| if cell == '#' { '#' } else { '.' };
+        for (carved, replayed) in maze.data.iter().zip(&replayed.data) {
+            let carved: Vec<char> = carved.iter().map(|&cell| floor(cell)).collect();
+            assert_eq!(&carved, replayed);
+        }
+        assert_eq!(steps.len(), maze.open_cell_count());
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use serde::Serialize;
+
+use crate::Maze;
+
+type Point = (usize, usize);
+
+/// Corridors collapsed into a weighted graph: the nodes are junctions, dead ends, the
+/// start and the goals, and each edge is one corridor between two of them.
+pub struct NavGraph {
+    pub nodes: Vec<Point>,
+    pub edges: Vec<NavEdge>,
+}
+
+/// A corridor between the nodes at indices `from` and `to` that takes `weight` steps.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct NavEdge {
+    pub from: usize,
+    pub to: usize,
+    pub weight: usize,
+}
+
+/// Builds the navmesh of `maze`. A closed loop without any junction gets one of its
+/// cells as a node, with a single edge back to itself; an isolated cell is a node
+/// without edges.
+pub fn to_navmesh(maze: &Maze) -> NavGraph {
+    let open_cells: Vec<Point> = maze
+        .data
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.iter()
+                .enumerate()
+                .filter(|&(_, &c)| c != '#')
+                .map(move |(col, _)| (row, col))
+        })
+        .collect();
+    let neighbors = |cell: Point| -> Vec<Point> {
+        maze.open_neighbors_dir(cell)
+            .into_iter()
+            .map(|(next, _)| next)
+            .collect()
+    };
+
+    let mut graph = NavGraph {
+        nodes: Vec::new(),
+        edges: Vec::new(),
+    };
+    let mut index = HashMap::new();
+    for &cell in &open_cells {
+        if matches!(maze.data[cell.0][cell.1], 'S' | 'G') || neighbors(cell).len() != 2 {
+            index.insert(cell, graph.nodes.len());
+            graph.nodes.push(cell);
+        }
+    }
+
+    let mut used = HashSet::new();
+    for pass in 0..2 {
+        for &cell in &open_cells {
+            if pass == 0 && !index.contains_key(&cell) {
+                continue;
+            }
+            for first in neighbors(cell) {
+                if used.contains(&edge(cell, first)) {
+                    continue;
+                }
+                // The second pass only meets loops that no node touches.
+                if pass == 1 {
+                    index.insert(cell, graph.nodes.len());
+                    graph.nodes.push(cell);
+                }
+                let (mut previous, mut current, mut weight) = (cell, first, 1);
+                used.insert(edge(previous, current));
+                while !index.contains_key(&current) {
+                    let next = neighbors(current)
+                        .into_iter()
+                        .find(|&next| next != previous)
+                        .unwrap();
+                    (previous, current) = (current, next);
+                    used.insert(edge(previous, current));
+                    weight += 1;
+                }
+                graph.edges.push(NavEdge {
+                    from: index[&cell],
+                    to: index[&current],
+                    weight,
+                });
+            }
+        }
+    }
+    graph
+}
+
+impl NavGraph {
+    /// Cheapest route between two node cells by Dijkstra over the edge weights, as the
+    /// nodes visited and the total number of steps.
+    pub fn route(&self, from: Point, to: Point) -> Option<(Vec<Point>, usize)> {
+        let source = self.nodes.iter().position(|&node| node == from)?;
+        let target = self.nodes.iter().position(|&node| node == to)?;
+        let mut adjacent = vec![Vec::new(); self.nodes.len()];
+        for edge in &self.edges {
+            adjacent[edge.from].push((edge.to, edge.weight));
+            adjacent[edge.to].push((edge.from, edge.weight));
+        }
+
+        let mut costs = vec![usize::MAX; self.nodes.len()];
+        let mut parents = vec![None; self.nodes.len()];
+        let mut open = BinaryHeap::new();
+        costs[source] = 0;
+        open.push(Reverse((0, source)));
+        while let Some(Reverse((cost, node))) = open.pop() {
+            if cost > costs[node] {
+                continue;
+            }
+            if node == target {
+                let mut route = vec![self.nodes[node]];
+                let mut current = node;
+                while let Some(parent) = parents[current] {
+                    route.push(self.nodes[parent]);
+                    current = parent;
+                }
+                route.reverse();
+                return Some((route, cost));
+            }
+            for &(next, weight) in &adjacent[node] {
+                if cost + weight < costs[next] {
+                    costs[next] = cost + weight;
+                    parents[next] = Some(node);
+                    open.push(Reverse((cost + weight, next)));
+                }
+            }
+        }
+        None
+    }
+}
+
+fn edge(a: Point, b: Point) -> (Point, Point) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        find_goals, find_start,
+        solver::{Bfs, PathFinder},
+        test_support::generated,
+        transform::open_walls,
+    };
+
+    #[test]
+    fn the_coarse_route_costs_the_fine_bfs_distance() {
+        for seed in [174, 175] {
+            let mut maze = generated(seed);
+            open_walls(&mut maze, 0.2, &mut StdRng::seed_from_u64(seed));
+            let graph = to_navmesh(&maze);
+            let (route, cost) = graph
+                .route(find_start(&maze), find_goals(&maze)[0])
+                .unwrap();
+
+            let fine = Bfs.find_path(&maze).unwrap().into_path();
+            assert_eq!(cost, fine.len() - 1);
+            assert!(route.len() < fine.len());
+            assert!(graph.nodes.len() < maze.open_cell_count());
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use pathfinding::directed::{astar::astar, bfs::bfs};
+
+use crate::{
+    find_goals, find_start,
+    solver::{PathFinder, Solution, SolverAlgorithm},
+    Maze,
+};
+
+/// Breadth-first search backed by the `pathfinding` crate.
+pub struct PathfindingBfs;
+
+/// A* with a Manhattan-distance heuristic backed by the `pathfinding` crate.
+pub struct PathfindingAstar;
+
+impl PathFinder for PathfindingBfs {
+    fn find_path(&self, maze: &Maze) -> Option<Solution> {
+        bfs(
+            &find_start(maze),
+            |&cell| successors(maze, cell),
+            |&(row, col)| maze.data[row][col] == 'G',
+        )
+        .map(|path| Solution::new(path, SolverAlgorithm::PathfindingBfs, None))
+    }
+}
+
+impl PathFinder for PathfindingAstar {
+    fn find_path(&self, maze: &Maze) -> Option<Solution> {
+        let goals = find_goals(maze);
+        astar(
+            &find_start(maze),
+            |&cell| successors(maze, cell).into_iter().map(|next| (next, 1)),
+            |&(row, col)| {
+                goals
+                    .iter()
+                    .map(|&(goal_row, goal_col)| row.abs_diff(goal_row) + col.abs_diff(goal_col))
+                    .min()
+                    .unwrap_or(0)
+            },
+            |&(row, col)| maze.data[row][col] == 'G',
+        )
+        .map(|(path, _)| Solution::new(path, SolverAlgorithm::PathfindingAstar, None))
+    }
+}
+
+fn successors(maze: &Maze, cell: (usize, usize)) -> Vec<(usize, usize)> {
+    maze.open_neighbors_dir(cell)
+        .into_iter()
+        .map(|(next, _)| next)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solver::Bfs, test_support::generated};
+
+    #[test]
+    fn pathfinding_backends_agree_with_the_built_in_bfs() {
+        for seed in 0..5 {
+            let maze = generated(seed);
+            let expected = Bfs.find_path(&maze).unwrap().length;
+            assert_eq!(PathfindingBfs.find_path(&maze).unwrap().length, expected);
+            assert_eq!(PathfindingAstar.find_path(&maze).unwrap().length, expected);
+        }
+    }
+}
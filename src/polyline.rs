@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use crate::Maze;
+
+type Point = (usize, usize);
+
+impl Maze {
+    /// Traces the open cells into polylines for vector renderers. Each polyline keeps
+    /// only its endpoints and corners; dead ends and junctions end a polyline, and a
+    /// closed loop starts and ends at the same point. An isolated cell is a single point.
+    pub fn corridors_as_polylines(&self) -> Vec<Vec<Point>> {
+        let mut used = HashSet::new();
+        let mut polylines = Vec::new();
+
+        let is_break = |cell: Point| self.open_neighbors(cell).len() != 2;
+        for (row, line) in self.data.iter().enumerate() {
+            for (col, &c) in line.iter().enumerate() {
+                let cell = (row, col);
+                if c == '#' || !is_break(cell) {
+                    continue;
+                }
+                let neighbors = self.open_neighbors(cell);
+                if neighbors.is_empty() {
+                    polylines.push(vec![cell]);
+                }
+                for next in neighbors {
+                    if !used.contains(&edge(cell, next)) {
+                        polylines.push(self.trace(cell, next, &mut used, is_break));
+                    }
+                }
+            }
+        }
+
+        // Whatever is left consists of cycles where every cell has two neighbours.
+        for (row, line) in self.data.iter().enumerate() {
+            for (col, &c) in line.iter().enumerate() {
+                let cell = (row, col);
+                if c == '#' {
+                    continue;
+                }
+                for next in self.open_neighbors(cell) {
+                    if !used.contains(&edge(cell, next)) {
+                        polylines.push(self.trace(cell, next, &mut used, |point| point == cell));
+                    }
+                }
+            }
+        }
+
+        polylines
+    }
+
+    /// Walks from `from` through `next` until `stop` holds, marking edges as used and
+    /// keeping only the cells where the direction changes.
+    fn trace<F: Fn(Point) -> bool>(
+        &self,
+        from: Point,
+        mut next: Point,
+        used: &mut HashSet<(Point, Point)>,
+        stop: F,
+    ) -> Vec<Point> {
+        let mut points = vec![from];
+        let mut previous = from;
+        loop {
+            used.insert(edge(previous, next));
+            if stop(next) {
+                points.push(next);
+                return points;
+            }
+            let following = self
+                .open_neighbors(next)
+                .into_iter()
+                .find(|&cell| cell != previous)
+                .unwrap();
+            if direction(previous, next) != direction(next, following) {
+                points.push(next);
+            }
+            previous = next;
+            next = following;
+        }
+    }
+
+    fn open_neighbors(&self, cell: Point) -> Vec<Point> {
+        self.open_neighbors_dir(cell)
+            .into_iter()
+            .map(|(next, _)| next)
+            .collect()
+    }
+}
+
+fn edge(a: Point, b: Point) -> (Point, Point) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn direction(from: Point, to: Point) -> (isize, isize) {
+    (
+        to.0 as isize - from.0 as isize,
+        to.1 as isize - from.1 as isize,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::read_maze;
+
+    #[test]
+    fn a_straight_corridor_is_one_two_point_polyline() {
+        let maze = read_maze("#######\n#S...G#\n#######\n".as_bytes()).unwrap();
+        assert_eq!(maze.corridors_as_polylines(), vec![vec![(1, 1), (1, 5)]]);
+    }
+
+    #[test]
+    fn a_junction_breaks_the_polylines() {
+        let maze = read_maze("#######\n#S...G#\n###.###\n###.###\n#######\n".as_bytes()).unwrap();
+        let mut polylines = maze.corridors_as_polylines();
+        polylines.sort();
+        assert_eq!(
+            polylines,
+            vec![
+                vec![(1, 1), (1, 3)],
+                vec![(1, 3), (1, 5)],
+                vec![(1, 3), (3, 3)],
+            ]
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use std::time::Instant;
+
+use serde::Serialize;
+
+use crate::retry::{write_file, RetryPolicy};
+
+/// Nanoseconds spent in each phase of the pipeline, summed over a batch.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct Profile {
+    pub read: u64,
+    pub generate: u64,
+    pub solve: u64,
+    pub serialize: u64,
+}
+
+impl Profile {
+    pub fn add(&mut self, other: &Profile) {
+        self.read += other.read;
+        self.generate += other.generate;
+        self.solve += other.solve;
+        self.serialize += other.serialize;
+    }
+}
+
+/// Runs `op` and adds its wall-clock duration to `phase`.
+pub fn timed<T>(phase: &mut u64, op: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let value = op();
+    *phase += started.elapsed().as_nanos() as u64;
+    value
+}
+
+/// Writes the phase timings as pretty JSON and returns the bytes written.
+pub fn write_profile(
+    profile: &Profile,
+    filename: &str,
+    retry: &RetryPolicy,
+) -> Result<Vec<u8>, std::io::Error> {
+    let json_string = serde_json::to_string_pretty(profile)?;
+    write_file(filename, json_string.as_bytes(), retry)?;
+    Ok(json_string.into_bytes())
+}
@@ -0,0 +1,207 @@
+use std::{collections::HashSet, fmt::Write, str::FromStr};
+
+use crate::{error::MazeError, Maze, CELL_MAPPINGS};
+
+const SOLUTION_COLOR: &str = "#ffc800";
+const UNKNOWN_COLOR: &str = "#808080";
+
+/// How [`render`] draws the grid for a terminal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderStyle {
+    /// The maze's own characters; solution cells become `*`.
+    #[default]
+    Plain,
+    /// Block characters for walls and fog; solution cells become `•`.
+    Unicode,
+    /// Two-space cells with 24-bit background colours from [`CELL_MAPPINGS`].
+    Ansi,
+}
+
+impl FromStr for RenderStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(RenderStyle::Plain),
+            "unicode" => Ok(RenderStyle::Unicode),
+            "ansi" => Ok(RenderStyle::Ansi),
+            _ => Err(format!(
+                "invalid render style '{}': expected plain, unicode or ansi",
+                s
+            )),
+        }
+    }
+}
+
+impl RenderStyle {
+    /// The style to use when only ASCII may be written: Unicode falls back to plain,
+    /// while ANSI escapes are ASCII already.
+    pub fn ascii(self) -> RenderStyle {
+        match self {
+            RenderStyle::Unicode => RenderStyle::Plain,
+            style => style,
+        }
+    }
+}
+
+/// Renders as [`render`] does with [`RenderStyle::ascii`], failing if the result still
+/// contains a non-ASCII character, e.g. from a maze read leniently.
+pub fn render_ascii(
+    maze: &Maze,
+    style: RenderStyle,
+    solution: Option<&[(usize, usize)]>,
+) -> Result<String, MazeError> {
+    let output = render(maze, style.ascii(), solution);
+    ensure_ascii(&output)?;
+    Ok(output)
+}
+
+/// Checks that `text` is pure ASCII, reporting the first offending row.
+pub fn ensure_ascii(text: &str) -> Result<(), MazeError> {
+    match text.lines().position(|line| !line.is_ascii()) {
+        Some(row) => Err(MazeError::NonAsciiOutput { row }),
+        None => Ok(()),
+    }
+}
+
+/// Draws `maze` one line per row, overlaying `solution` when given. Start and goal keep
+/// their markers under the overlay.
+pub fn render(maze: &Maze, style: RenderStyle, solution: Option<&[(usize, usize)]>) -> String {
+    let on_path: HashSet<_> = solution.unwrap_or_default().iter().copied().collect();
+    let mut output = String::new();
+
+    for (row, line) in maze.data.iter().enumerate() {
+        for (col, &cell) in line.iter().enumerate() {
+            let path = on_path.contains(&(row, col)) && cell != 'S' && cell != 'G';
+            match style {
+                RenderStyle::Plain => output.push(if path { '*' } else { cell }),
+                RenderStyle::Unicode => output.push(match cell {
+                    _ if path => '•',
+                    '#' => '█',
+                    '?' => '░',
+                    '.' => ' ',
+                    _ => cell,
+                }),
+                RenderStyle::Ansi => {
+                    let color = if path {
+                        SOLUTION_COLOR
+                    } else {
+                        CELL_MAPPINGS
+                            .iter()
+                            .find(|mapping| mapping.symbol == cell)
+                            .map_or(UNKNOWN_COLOR, |mapping| mapping.color)
+                    };
+                    let (r, g, b) = hex_rgb(color);
+                    let text = if cell.is_ascii_digit() {
+                        format!("{} ", cell)
+                    } else {
+                        "  ".to_string()
+                    };
+                    let _ = write!(output, "\x1b[48;2;{};{};{}m\x1b[30m{}", r, g, b, text);
+                }
+            }
+        }
+        if style == RenderStyle::Ansi {
+            output.push_str("\x1b[0m");
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Prefixes `rendered`, the output of [`render`] in `style`, with coordinate rulers: a
+/// header of column indices and each row's index in the left margin, both mod 10 so
+/// they stay one digit wide on any grid.
+pub fn add_rulers(rendered: &str, style: RenderStyle, cols: usize) -> String {
+    let cell_width = if style == RenderStyle::Ansi { 2 } else { 1 };
+    let mut output = String::from("  ");
+    for col in 0..cols {
+        let _ = write!(output, "{:<width$}", col % 10, width = cell_width);
+    }
+    output.truncate(output.trim_end().len());
+    output.push('\n');
+    for (row, line) in rendered.lines().enumerate() {
+        let _ = writeln!(output, "{} {}", row % 10, line);
+    }
+    output
+}
+
+fn hex_rgb(color: &str) -> (u8, u8, u8) {
+    let channel = |i: usize| u8::from_str_radix(&color[i..i + 2], 16).unwrap_or(0);
+    (channel(1), channel(3), channel(5))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solve_maze, test_support::maze};
+
+    #[test]
+    fn the_overlay_is_optional_at_the_same_call_site() {
+        let maze = maze("#######\n#S..#.#\n###.#.#\n#....G#\n#######\n");
+        let solution = solve_maze(&maze).unwrap();
+        for (overlay, expected) in [
+            (None, "#######\n#S..#.#\n###.#.#\n#....G#\n#######\n"),
+            (
+                Some(solution.as_slice()),
+                "#######\n#S**#.#\n###*#.#\n#..**G#\n#######\n",
+            ),
+        ] {
+            assert_eq!(render(&maze, RenderStyle::Plain, overlay), expected);
+        }
+        let clean = render(&maze, RenderStyle::Unicode, None);
+        assert!(!clean.contains('•'));
+        assert_eq!(
+            render(&maze, RenderStyle::Unicode, Some(&solution))
+                .matches('•')
+                .count(),
+            solution.len() - 2
+        );
+    }
+
+    #[test]
+    fn ascii_mode_output_is_pure_ascii() {
+        let maze = maze("#######\n#S..#.#\n###.#?#\n#....G#\n#######\n");
+        let solution = solve_maze(&maze).unwrap();
+        for style in [RenderStyle::Plain, RenderStyle::Unicode, RenderStyle::Ansi] {
+            let output = render_ascii(&maze, style, Some(&solution)).unwrap();
+            assert!(output.is_ascii(), "{:?} rendered non-ASCII output", style);
+        }
+        assert!(!render(&maze, RenderStyle::Unicode, None).is_ascii());
+        assert!(matches!(
+            ensure_ascii("ok\nnot ok: █\n"),
+            Err(MazeError::NonAsciiOutput { row: 1 })
+        ));
+    }
+
+    #[test]
+    fn the_ruler_header_spans_every_column() {
+        let maze = maze(&format!(
+            "{}\n#S{}G#\n{}\n",
+            "#".repeat(14),
+            ".".repeat(10),
+            "#".repeat(14)
+        ));
+        let plain = add_rulers(
+            &render(&maze, RenderStyle::Plain, None),
+            RenderStyle::Plain,
+            maze.cols,
+        );
+        let lines: Vec<&str> = plain.lines().collect();
+        assert_eq!(lines[0], "  01234567890123");
+        assert_eq!(lines[0].len() - 2, maze.cols);
+        assert_eq!(lines[1], format!("0 {}", "#".repeat(14)));
+        assert!(lines[2].starts_with("1 #S"));
+
+        let ansi = add_rulers(
+            &render(&maze, RenderStyle::Ansi, None),
+            RenderStyle::Ansi,
+            maze.cols,
+        );
+        assert_eq!(
+            ansi.lines().next().unwrap().trim_start().len(),
+            2 * maze.cols - 1
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::{fs::File, io, io::Write, thread, time::Duration};
+
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    pub delay: Duration,
+}
+
+/// Runs `op` up to `policy.attempts` times, doubling the delay after each failure,
+/// and returns the last error if every attempt fails.
+pub fn with_retry<T, E>(
+    policy: &RetryPolicy,
+    mut op: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let mut delay = policy.delay;
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt >= policy.attempts => return Err(err),
+            Err(_) => {
+                thread::sleep(delay);
+                delay = delay.saturating_mul(2);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+pub fn write_file(filename: &str, bytes: &[u8], policy: &RetryPolicy) -> io::Result<()> {
+    with_retry(policy, || File::create(filename)?.write_all(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fails the first `failures` writes, then accepts everything.
+    struct FlakyWriter {
+        failures: usize,
+        written: Vec<u8>,
+    }
+
+    impl Write for FlakyWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(io::Error::other("transient failure"));
+            }
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_succeeds_after_two_failures() {
+        let policy = RetryPolicy {
+            attempts: 3,
+            delay: Duration::from_millis(1),
+        };
+        let mut writer = FlakyWriter {
+            failures: 2,
+            written: Vec::new(),
+        };
+        with_retry(&policy, || writer.write_all(b"maze")).unwrap();
+        assert_eq!(writer.written, b"maze");
+
+        let policy = RetryPolicy {
+            attempts: 2,
+            ..policy
+        };
+        let mut writer = FlakyWriter {
+            failures: 2,
+            written: Vec::new(),
+        };
+        assert!(with_retry(&policy, || writer.write_all(b"maze")).is_err());
+        assert!(writer.written.is_empty());
+    }
+}
@@ -0,0 +1,174 @@
+use std::{fs::File, io::BufReader};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::MazeError,
+    retry::{write_file, RetryPolicy},
+    Maze, Position,
+};
+
+/// Each row is a list of alternating run lengths, always starting with a wall run
+/// (which may be zero). Start and goal count as floor in the runs and are restored
+/// from their coordinates, so only walls, floor and one start and goal can be encoded.
+#[derive(Serialize, Deserialize)]
+struct RleJson {
+    width: usize,
+    height: usize,
+    #[serde(default)]
+    start: Option<Position>,
+    #[serde(default)]
+    goal: Option<Position>,
+    rows: Vec<Vec<usize>>,
+    solution: Vec<Position>,
+}
+
+/// Writes the maze run-length encoded and returns the bytes written.
+pub fn create_rle_file(
+    maze: &Maze,
+    solution: &[(usize, usize)],
+    filename: &str,
+    retry: &RetryPolicy,
+) -> Result<Vec<u8>, MazeError> {
+    let mut start = None;
+    let mut goal = None;
+    let mut rows = Vec::with_capacity(maze.rows);
+
+    for (y, line) in maze.data.iter().enumerate() {
+        let mut runs = Vec::new();
+        let mut wall = true;
+        let mut run = 0;
+        for (x, &cell) in line.iter().enumerate() {
+            match cell {
+                'S' if start.is_none() => start = Some(Position::from((y, x))),
+                'G' if goal.is_none() => goal = Some(Position::from((y, x))),
+                '#' | '.' => {}
+                _ => {
+                    return Err(MazeError::UnencodableCell {
+                        row: y,
+                        col: x,
+                        cell,
+                    })
+                }
+            }
+            if (cell == '#') != wall {
+                runs.push(run);
+                wall = !wall;
+                run = 0;
+            }
+            run += 1;
+        }
+        runs.push(run);
+        rows.push(runs);
+    }
+
+    let rle = RleJson {
+        width: maze.cols,
+        height: maze.rows,
+        start: Some(start.ok_or(MazeError::MissingEndpoint { marker: 'S' })?),
+        goal: Some(goal.ok_or(MazeError::MissingEndpoint { marker: 'G' })?),
+        rows,
+        solution: solution.iter().map(|&cell| Position::from(cell)).collect(),
+    };
+
+    let json_string = serde_json::to_string(&rle)?;
+    write_file(filename, json_string.as_bytes(), retry)?;
+    Ok(json_string.into_bytes())
+}
+
+pub fn read_maze_rle(filename: &str) -> Result<Maze, MazeError> {
+    let rle: RleJson = serde_json::from_reader(BufReader::new(File::open(filename)?))?;
+
+    let mut data = Vec::with_capacity(rle.height);
+    for (row, runs) in rle.rows.iter().enumerate() {
+        let mut line = Vec::with_capacity(rle.width);
+        for (i, &run) in runs.iter().enumerate() {
+            let cell = if i % 2 == 0 { '#' } else { '.' };
+            line.extend(std::iter::repeat_n(cell, run));
+        }
+        if line.len() != rle.width {
+            return Err(MazeError::InvalidRle { row });
+        }
+        data.push(line);
+    }
+    if data.len() != rle.height {
+        return Err(MazeError::InvalidRle { row: data.len() });
+    }
+
+    for (marker, position) in [('S', &rle.start), ('G', &rle.goal)] {
+        let position = position
+            .as_ref()
+            .ok_or(MazeError::MissingEndpoint { marker })?;
+        match data
+            .get_mut(position.y)
+            .and_then(|row| row.get_mut(position.x))
+        {
+            Some(cell) => *cell = marker,
+            None => return Err(MazeError::InvalidRle { row: position.y }),
+        }
+    }
+
+    Ok(Maze {
+        rows: rle.height,
+        cols: rle.width,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{
+        read_maze, solve_maze,
+        test_support::{generated, no_retry, temp_path},
+    };
+
+    #[test]
+    fn rle_round_trip_gives_the_original_grid() {
+        let maze = generated(11);
+        let solution = solve_maze(&maze).unwrap();
+        let filename = &temp_path("rle.json");
+        let retry = no_retry();
+        create_rle_file(&maze, &solution, filename, &retry).unwrap();
+        let decoded = read_maze_rle(filename).unwrap();
+        std::fs::remove_file(filename).unwrap();
+
+        assert_eq!((decoded.rows, decoded.cols), (maze.rows, maze.cols));
+        assert_eq!(decoded.data, maze.data);
+    }
+
+    #[test]
+    fn cells_the_runs_cannot_hold_are_rejected() {
+        let filename = &temp_path("rle-cells.json");
+        let retry = no_retry();
+        for cell in ['9', 'D', '~'] {
+            let text = format!("#######\n#S.{}.G#\n#######\n", cell);
+            let maze = read_maze(text.as_bytes()).unwrap();
+            assert!(matches!(
+                create_rle_file(&maze, &[], filename, &retry),
+                Err(MazeError::UnencodableCell { row: 1, col: 3, cell: c }) if c == cell
+            ));
+        }
+        let no_goal = read_maze("#####\n#S..#\n#####\n".as_bytes()).unwrap();
+        assert!(matches!(
+            create_rle_file(&no_goal, &[], filename, &retry),
+            Err(MazeError::MissingEndpoint { marker: 'G' })
+        ));
+        assert!(!std::path::Path::new(filename).exists());
+    }
+
+    #[test]
+    fn a_file_without_a_start_is_rejected() {
+        let filename = &temp_path("rle-start.json");
+        let json =
+            r#"{"width":5,"height":3,"goal":{"x":3,"y":1},"rows":[[5],[1,3,1],[5]],"solution":[]}"#;
+        std::fs::write(filename, json).unwrap();
+        let result = read_maze_rle(filename);
+        std::fs::remove_file(filename).unwrap();
+        assert!(matches!(
+            result,
+            Err(MazeError::MissingEndpoint { marker: 'S' })
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{cell_weight, Maze, Move};
+
+/// How the solver treats one kind of cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CellRule {
+    pub symbol: char,
+    /// Cost of entering the cell.
+    pub cost: u32,
+    /// Moves that may enter the cell; `None` allows every direction.
+    pub enter: Option<Vec<Move>>,
+}
+
+impl CellRule {
+    /// A cell such as a `~` river that can be entered from anywhere at `cost`.
+    pub fn weighted(symbol: char, cost: u32) -> Self {
+        CellRule {
+            symbol,
+            cost,
+            enter: None,
+        }
+    }
+
+    /// A cell such as a `D` door that can only be entered with `step`, i.e. from the
+    /// opposite side; leaving it is unrestricted.
+    pub fn one_way(symbol: char, step: Move) -> Self {
+        CellRule {
+            symbol,
+            cost: 1,
+            enter: Some(vec![step]),
+        }
+    }
+}
+
+/// Cell semantics consulted by [`crate::solver::dijkstra_with_rules`] when expanding
+/// neighbours. A cell without a rule is a wall if it is `#` and otherwise costs its
+/// [`cell_weight`], so the default empty table describes the plain maze.
+#[derive(Clone, Debug, Default)]
+pub struct RuleTable {
+    pub rules: Vec<CellRule>,
+}
+
+impl RuleTable {
+    pub fn new(rules: Vec<CellRule>) -> Self {
+        RuleTable { rules }
+    }
+
+    pub fn rule(&self, symbol: char) -> Option<&CellRule> {
+        self.rules.iter().find(|rule| rule.symbol == symbol)
+    }
+
+    /// Cost of entering a `cell` with `step`, or `None` if the table forbids it.
+    pub fn entry_cost(&self, cell: char, step: Move) -> Option<u32> {
+        match self.rule(cell) {
+            Some(rule) => rule
+                .enter
+                .as_ref()
+                .is_none_or(|moves| moves.contains(&step))
+                .then_some(rule.cost),
+            None => (cell != '#').then(|| cell_weight(cell)),
+        }
+    }
+
+    /// Neighbours of `cell` that the table allows entering, with the move and its cost,
+    /// in [`Move::ALL`] order.
+    pub fn neighbors(&self, maze: &Maze, cell: (usize, usize)) -> Vec<((usize, usize), Move, u32)> {
+        maze.open_neighbors_dir(cell)
+            .into_iter()
+            .filter_map(|(next, step)| {
+                self.entry_cost(maze.data[next.0][next.1], step)
+                    .map(|cost| (next, step, cost))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_maze, solver::dijkstra_with_rules};
+
+    const DOOR: &str = "#######\n#S.D.G#\n#.###.#\n#.....#\n#######\n";
+
+    #[test]
+    fn a_door_entered_from_the_wrong_side_forces_a_detour() {
+        let maze = read_maze(DOOR.as_bytes()).unwrap();
+        let solution = dijkstra_with_rules(&maze, &RuleTable::default()).unwrap();
+        assert_eq!((solution.length, solution.cost), (5, Some(4)));
+
+        // Walking from S the door is entered moving right, which only Left allows.
+        let rules = RuleTable::new(vec![CellRule::one_way('D', Move::Left)]);
+        let solution = dijkstra_with_rules(&maze, &rules).unwrap();
+        assert_eq!((solution.length, solution.cost), (9, Some(8)));
+        assert!(!solution.path.contains(&(1, 3)));
+
+        let rules = RuleTable::new(vec![CellRule::one_way('D', Move::Right)]);
+        assert_eq!(dijkstra_with_rules(&maze, &rules).unwrap().cost, Some(4));
+    }
+
+    #[test]
+    fn an_expensive_river_is_walked_around() {
+        let maze = read_maze(DOOR.replace('D', "~").as_bytes()).unwrap();
+        let rules = RuleTable::new(vec![CellRule::weighted('~', 10)]);
+        assert_eq!(dijkstra_with_rules(&maze, &rules).unwrap().cost, Some(8));
+        let rules = RuleTable::new(vec![CellRule::weighted('~', 2)]);
+        assert_eq!(dijkstra_with_rules(&maze, &rules).unwrap().cost, Some(5));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::Maze;
+
+/// String-pulls a grid path for consumers that move in any direction: from each kept
+/// point, skips ahead to the farthest later point that is still in line of sight. The
+/// first and last points are always kept, so the result never has more points.
+pub fn smooth_path(maze: &Maze, path: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let Some((&first, rest)) = path.split_first() else {
+        return Vec::new();
+    };
+
+    let mut smoothed = vec![first];
+    let mut anchor = first;
+    for window in rest.windows(2) {
+        let (current, next) = (window[0], window[1]);
+        if !line_of_sight(maze, anchor, next) {
+            smoothed.push(current);
+            anchor = current;
+        }
+    }
+    if let Some(&last) = rest.last() {
+        smoothed.push(last);
+    }
+    smoothed
+}
+
+/// Whether the straight segment between two cell centres crosses only open cells. The
+/// walk visits every cell the segment touches, and passing exactly through a corner
+/// needs both cells beside the corner open so the line never squeezes between walls.
+pub fn line_of_sight(maze: &Maze, from: (usize, usize), to: (usize, usize)) -> bool {
+    let open = |row: i64, col: i64| {
+        row >= 0
+            && col >= 0
+            && maze
+                .data
+                .get(row as usize)
+                .and_then(|line| line.get(col as usize))
+                .is_some_and(|&c| c != '#')
+    };
+
+    let (mut row, mut col) = (from.0 as i64, from.1 as i64);
+    let (dy, dx) = (to.0 as i64 - row, to.1 as i64 - col);
+    let (ny, nx) = (dy.abs(), dx.abs());
+    let (sy, sx) = (dy.signum(), dx.signum());
+    let (mut iy, mut ix) = (0, 0);
+
+    if !open(row, col) {
+        return false;
+    }
+    while ix < nx || iy < ny {
+        let x_crossing = (1 + 2 * ix) * ny;
+        let y_crossing = (1 + 2 * iy) * nx;
+        if x_crossing == y_crossing {
+            if !open(row, col + sx) || !open(row + sy, col) {
+                return false;
+            }
+            row += sy;
+            col += sx;
+            ix += 1;
+            iy += 1;
+        } else if x_crossing < y_crossing {
+            col += sx;
+            ix += 1;
+        } else {
+            row += sy;
+            iy += 1;
+        }
+        if !open(row, col) {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_maze, solve_maze};
+
+    /// The smoothed solution of `text`, checked to keep line of sight and never to have
+    /// more points than the grid path.
+    fn smoothed(text: &str) -> Vec<(usize, usize)> {
+        let maze = read_maze(text.as_bytes()).unwrap();
+        let path = solve_maze(&maze).unwrap();
+        let smoothed = smooth_path(&maze, &path);
+        for pair in smoothed.windows(2) {
+            assert!(line_of_sight(&maze, pair[0], pair[1]));
+        }
+        assert!(smoothed.len() <= path.len());
+        smoothed
+    }
+
+    #[test]
+    fn an_open_room_smooths_to_a_straight_line() {
+        let smoothed = smoothed("#######\n#S....#\n#.....#\n#....G#\n#######\n");
+        assert_eq!(smoothed, [(1, 1), (3, 5)]);
+    }
+
+    #[test]
+    fn smoothing_keeps_the_corner_of_a_bend() {
+        let smoothed = smoothed("#####\n#S..#\n###.#\n###G#\n#####\n");
+        assert_eq!(smoothed, [(1, 1), (1, 3), (3, 3)]);
+    }
+}
@@ -0,0 +1,281 @@
+use std::{collections::VecDeque, fmt};
+
+use serde::Serialize;
+
+use crate::{cell_weight, find_goals, find_start, solver::SolverAlgorithm, Maze};
+
+/// Modulus for [`count_shortest_paths`]; counts grow exponentially with open area.
+pub const PATH_COUNT_MODULUS: u64 = 1_000_000_007;
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub path_length: usize,
+    /// Sum of the weights of every cell entered after the start.
+    pub path_cost: u64,
+    pub dead_ends: usize,
+    pub junctions: usize,
+    pub wall_density: f64,
+    pub open_density: f64,
+    pub difficulty: f64,
+    pub loops: usize,
+    /// Whether exactly one shortest path leads from the start to the nearest goal.
+    pub unique_solution: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shortest_path_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub corridor_width_profile: Option<Vec<usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solver: Option<SolverAlgorithm>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expanded_nodes: Option<usize>,
+}
+
+/// Dead ends are open cells with a single open neighbour (start and goal excluded),
+/// junctions are open cells with three or more. The difficulty score is the solution
+/// length scaled up by how much of the open area branches off into dead ends.
+pub fn compute_stats(maze: &Maze, path: &[(usize, usize)]) -> Stats {
+    let mut walls = 0;
+    let mut open = 0;
+    let mut dead_ends = 0;
+    let mut junctions = 0;
+
+    for (row, line) in maze.data.iter().enumerate() {
+        for (col, &cell) in line.iter().enumerate() {
+            if cell == '#' {
+                walls += 1;
+                continue;
+            }
+            open += 1;
+            if cell == 'S' || cell == 'G' {
+                continue;
+            }
+            match open_neighbors(maze, row, col) {
+                1 => dead_ends += 1,
+                n if n >= 3 => junctions += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let total = maze.rows * maze.cols;
+    let wall_density = if total == 0 {
+        0.0
+    } else {
+        walls as f64 / total as f64
+    };
+    let difficulty = if open == 0 {
+        0.0
+    } else {
+        path.len() as f64 * (1.0 + dead_ends as f64 / open as f64)
+    };
+
+    Stats {
+        path_length: path.len(),
+        path_cost: path
+            .iter()
+            .skip(1)
+            .map(|&(row, col)| cell_weight(maze.data[row][col]) as u64)
+            .sum(),
+        dead_ends,
+        junctions,
+        wall_density,
+        open_density: maze.open_density(),
+        difficulty,
+        loops: loop_count(maze),
+        unique_solution: has_unique_shortest_path(maze),
+        shortest_path_count: None,
+        corridor_width_profile: None,
+        solver: None,
+        expanded_nodes: None,
+    }
+}
+
+/// Number of independent cycles in the open-cell graph: edges - nodes + components.
+/// A perfect maze has none.
+pub fn loop_count(maze: &Maze) -> usize {
+    let open = |row: usize, col: usize| maze.data[row][col] != '#';
+    let mut nodes = 0usize;
+    let mut edges = 0usize;
+    let mut components = 0usize;
+    let mut seen = vec![vec![false; maze.cols]; maze.rows];
+
+    for row in 0..maze.rows {
+        for col in 0..maze.cols {
+            if !open(row, col) {
+                continue;
+            }
+            nodes += 1;
+            if col + 1 < maze.cols && open(row, col + 1) {
+                edges += 1;
+            }
+            if row + 1 < maze.rows && open(row + 1, col) {
+                edges += 1;
+            }
+            if !seen[row][col] {
+                components += 1;
+                seen[row][col] = true;
+                let mut stack = vec![(row, col)];
+                while let Some(cell) = stack.pop() {
+                    for ((r, c), _) in maze.open_neighbors_dir(cell) {
+                        if !seen[r][c] {
+                            seen[r][c] = true;
+                            stack.push((r, c));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    (edges + components).saturating_sub(nodes)
+}
+
+/// Number of distinct shortest paths from the start to the nearest goal, modulo
+/// [`PATH_COUNT_MODULUS`]. Each cell's count is the sum over its neighbours one BFS layer
+/// closer to the start, so no path is ever built. Zero if no goal is reachable.
+pub fn count_shortest_paths(maze: &Maze) -> u64 {
+    shortest_path_count_with(maze, |a, b| (a + b) % PATH_COUNT_MODULUS)
+}
+
+/// Whether the shortest path to the nearest goal is the only one. Counts saturate at 2
+/// rather than wrapping like [`count_shortest_paths`], so a count that happens to be 1
+/// modulo [`PATH_COUNT_MODULUS`] is not mistaken for a unique path. Always true for a
+/// perfect maze.
+pub fn has_unique_shortest_path(maze: &Maze) -> bool {
+    shortest_path_count_with(maze, |a, b| (a + b).min(2)) == 1
+}
+
+/// Counts shortest paths, with `add` combining the counts of two sets of paths.
+fn shortest_path_count_with<F: Fn(u64, u64) -> u64>(maze: &Maze, add: F) -> u64 {
+    let start = find_start(maze);
+    let mut distance = vec![vec![usize::MAX; maze.cols]; maze.rows];
+    let mut count = vec![vec![0u64; maze.cols]; maze.rows];
+    let mut queue = VecDeque::from([start]);
+    distance[start.0][start.1] = 0;
+    count[start.0][start.1] = 1;
+
+    while let Some((row, col)) = queue.pop_front() {
+        let next = distance[row][col] + 1;
+        for ((nr, nc), _) in maze.open_neighbors_dir((row, col)) {
+            if distance[nr][nc] == usize::MAX {
+                distance[nr][nc] = next;
+                queue.push_back((nr, nc));
+            }
+            if distance[nr][nc] == next {
+                count[nr][nc] = add(count[nr][nc], count[row][col]);
+            }
+        }
+    }
+
+    let goals = find_goals(maze);
+    let nearest = goals.iter().map(|&(row, col)| distance[row][col]).min();
+    match nearest {
+        Some(nearest) if nearest != usize::MAX => goals
+            .iter()
+            .filter(|&&(row, col)| distance[row][col] == nearest)
+            .fold(0, |total, &(row, col)| add(total, count[row][col])),
+        _ => 0,
+    }
+}
+
+/// Open width across the direction of travel at each cell of `path`: the run of open
+/// cells through the cell perpendicular to the step leaving it (the step entering it for
+/// the last cell). A plain corridor is 1 wide; the profile spikes where the path crosses
+/// a room. A path of one cell has width 1.
+pub fn corridor_width_profile(maze: &Maze, path: &[(usize, usize)]) -> Vec<usize> {
+    let open = |row: i64, col: i64| {
+        row >= 0
+            && col >= 0
+            && maze
+                .data
+                .get(row as usize)
+                .and_then(|line| line.get(col as usize))
+                .is_some_and(|&cell| cell != '#')
+    };
+
+    (0..path.len())
+        .map(|i| {
+            let (from, to) = match (path.get(i + 1), i.checked_sub(1)) {
+                (Some(&next), _) => (path[i], next),
+                (None, Some(previous)) => (path[previous], path[i]),
+                (None, None) => return 1,
+            };
+            // Rotating the step by 90 degrees gives the perpendicular axis.
+            let (dr, dc) = (to.1 as i64 - from.1 as i64, from.0 as i64 - to.0 as i64);
+            let (row, col) = (path[i].0 as i64, path[i].1 as i64);
+            let run = |sign: i64| {
+                (1..)
+                    .take_while(|&k| open(row + sign * k * dr, col + sign * k * dc))
+                    .count()
+            };
+            1 + run(1) + run(-1)
+        })
+        .collect()
+}
+
+fn open_neighbors(maze: &Maze, row: usize, col: usize) -> usize {
+    maze.open_neighbors_dir((row, col)).len()
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "path_length={}", self.path_length)?;
+        writeln!(f, "path_cost={}", self.path_cost)?;
+        writeln!(f, "dead_ends={}", self.dead_ends)?;
+        writeln!(f, "junctions={}", self.junctions)?;
+        writeln!(f, "wall_density={:.4}", self.wall_density)?;
+        writeln!(f, "open_density={:.4}", self.open_density)?;
+        writeln!(f, "difficulty={:.4}", self.difficulty)?;
+        writeln!(f, "loops={}", self.loops)?;
+        write!(f, "unique_solution={}", self.unique_solution)?;
+        if let Some(count) = self.shortest_path_count {
+            write!(f, "\nshortest_path_count={}", count)?;
+        }
+        if let Some(profile) = &self.corridor_width_profile {
+            let widths: Vec<String> = profile.iter().map(usize::to_string).collect();
+            write!(f, "\ncorridor_width_profile={}", widths.join(","))?;
+        }
+        if let Some(solver) = self.solver {
+            write!(f, "\nsolver={}", solver.name())?;
+        }
+        if let Some(expanded) = self.expanded_nodes {
+            write!(f, "\nexpanded_nodes={}", expanded)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{generated, maze};
+
+    #[test]
+    fn an_open_room_has_a_binomial_number_of_shortest_paths() {
+        // 3 steps down and 4 right in any order: 7 choose 3.
+        let room = maze("#######\n#S....#\n#.....#\n#.....#\n#....G#\n#######\n");
+        assert_eq!(count_shortest_paths(&room), 35);
+        let corridor = maze("#######\n#S...G#\n#######\n");
+        assert_eq!(count_shortest_paths(&corridor), 1);
+    }
+
+    #[test]
+    fn a_symmetric_loop_makes_the_solution_ambiguous() {
+        let looped = maze("#####\n#S..#\n#.#.#\n#..G#\n#####\n");
+        assert!(!has_unique_shortest_path(&looped));
+        assert!(!compute_stats(&looped, &[]).unique_solution);
+
+        for seed in 0..5 {
+            let perfect = generated(seed);
+            assert!(has_unique_shortest_path(&perfect), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn the_width_profile_spikes_in_a_room() {
+        let room = maze("#########\n###...###\n#S.....G#\n###...###\n#########\n");
+        let path: Vec<_> = (1..=7).map(|col| (2, col)).collect();
+        assert_eq!(corridor_width_profile(&room, &path), [1, 1, 3, 3, 3, 1, 1]);
+        assert_eq!(corridor_width_profile(&room, &path[..1]), [1]);
+    }
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{
+    retry::{write_file, RetryPolicy},
+    stats::Stats,
+};
+
+/// Minimum, median, mean and maximum of one metric over a batch.
+#[derive(Serialize)]
+pub struct Aggregate {
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub max: f64,
+}
+
+/// Aggregates of the per-maze [`Stats`] of a batch.
+#[derive(Serialize)]
+pub struct Summary {
+    pub mazes: usize,
+    pub path_length: Aggregate,
+    pub dead_ends: Aggregate,
+    pub difficulty: Aggregate,
+}
+
+pub fn summarize(stats: &[Stats]) -> Summary {
+    Summary {
+        mazes: stats.len(),
+        path_length: aggregate(stats.iter().map(|s| s.path_length as f64).collect()),
+        dead_ends: aggregate(stats.iter().map(|s| s.dead_ends as f64).collect()),
+        difficulty: aggregate(stats.iter().map(|s| s.difficulty).collect()),
+    }
+}
+
+/// An empty batch aggregates to all zeros; the median of an even count is the mean of
+/// the two middle values.
+fn aggregate(mut values: Vec<f64>) -> Aggregate {
+    if values.is_empty() {
+        return Aggregate {
+            min: 0.0,
+            median: 0.0,
+            mean: 0.0,
+            max: 0.0,
+        };
+    }
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    let median = if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    };
+    Aggregate {
+        min: values[0],
+        median,
+        mean: values.iter().sum::<f64>() / values.len() as f64,
+        max: values[values.len() - 1],
+    }
+}
+
+/// Writes the summary as pretty JSON and returns the bytes written.
+pub fn write_summary(
+    summary: &Summary,
+    filename: &str,
+    retry: &RetryPolicy,
+) -> Result<Vec<u8>, std::io::Error> {
+    let json_string = serde_json::to_string_pretty(summary)?;
+    write_file(filename, json_string.as_bytes(), retry)?;
+    Ok(json_string.into_bytes())
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "mazes={}", self.mazes)?;
+        write!(
+            f,
+            "{:<12} {:>10} {:>10} {:>10} {:>10}",
+            "metric", "min", "median", "mean", "max"
+        )?;
+        for (name, aggregate) in [
+            ("path_length", &self.path_length),
+            ("dead_ends", &self.dead_ends),
+            ("difficulty", &self.difficulty),
+        ] {
+            write!(
+                f,
+                "\n{:<12} {:>10.2} {:>10.2} {:>10.2} {:>10.2}",
+                name, aggregate.min, aggregate.median, aggregate.mean, aggregate.max
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solve_maze, stats::compute_stats, test_support::generated};
+
+    #[test]
+    fn aggregates_match_the_batch() {
+        let stats: Vec<Stats> = (0..4)
+            .map(|seed| {
+                let maze = generated(seed);
+                compute_stats(&maze, &solve_maze(&maze).unwrap())
+            })
+            .collect();
+        let summary = summarize(&stats);
+        assert_eq!(summary.mazes, 4);
+
+        let mut lengths: Vec<f64> = stats.iter().map(|s| s.path_length as f64).collect();
+        lengths.sort_by(f64::total_cmp);
+        assert_eq!(summary.path_length.min, lengths[0]);
+        assert_eq!(summary.path_length.max, lengths[3]);
+        assert_eq!(summary.path_length.median, (lengths[1] + lengths[2]) / 2.0);
+        assert_eq!(summary.path_length.mean, lengths.iter().sum::<f64>() / 4.0);
+        let dead_ends = stats.iter().map(|s| s.dead_ends).sum::<usize>() as f64 / 4.0;
+        assert_eq!(summary.dead_ends.mean, dead_ends);
+        assert!(summary.difficulty.min <= summary.difficulty.median);
+        assert!(summary.difficulty.median <= summary.difficulty.max);
+    }
+
+    #[test]
+    fn an_odd_batch_takes_the_middle_value_as_median() {
+        let aggregate = aggregate(vec![5.0, 1.0, 3.0]);
+        assert_eq!(
+            (
+                aggregate.min,
+                aggregate.median,
+                aggregate.mean,
+                aggregate.max
+            ),
+            (1.0, 3.0, 3.0, 5.0)
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use std::str::FromStr;
+
+/// Output file name pattern with `{w}`, `{h}`, `{seed}`, `{i}` and `{ext}` placeholders,
+/// checked when parsed so a typo fails before anything is generated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputTemplate(String);
+
+/// Values substituted into an [`OutputTemplate`].
+pub struct TemplateFields<'a> {
+    pub width: usize,
+    pub height: usize,
+    pub seed: u64,
+    pub index: usize,
+    pub ext: &'a str,
+}
+
+const PLACEHOLDERS: [&str; 5] = ["w", "h", "seed", "i", "ext"];
+
+impl OutputTemplate {
+    /// Whether two mazes of a batch get different names, i.e. `{seed}` or `{i}` appears.
+    pub fn is_unique_per_maze(&self) -> bool {
+        self.0.contains("{seed}") || self.0.contains("{i}")
+    }
+
+    pub fn render(&self, fields: &TemplateFields) -> String {
+        self.0
+            .replace("{w}", &fields.width.to_string())
+            .replace("{h}", &fields.height.to_string())
+            .replace("{seed}", &fields.seed.to_string())
+            .replace("{i}", &fields.index.to_string())
+            .replace("{ext}", fields.ext)
+    }
+}
+
+impl FromStr for OutputTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rest = s;
+        while let Some(open) = rest.find('{') {
+            if rest[..open].contains('}') {
+                return Err(format!("unmatched '}}' in output template '{}'", s));
+            }
+            let Some(close) = rest[open..].find('}') else {
+                return Err(format!("unclosed '{{' in output template '{}'", s));
+            };
+            let name = &rest[open + 1..open + close];
+            if !PLACEHOLDERS.contains(&name) {
+                return Err(format!(
+                    "unknown placeholder '{{{}}}' in output template: expected {{w}}, {{h}}, \
+                     {{seed}}, {{i}} or {{ext}}",
+                    name
+                ));
+            }
+            rest = &rest[open + close + 1..];
+        }
+        if rest.contains('}') {
+            return Err(format!("unmatched '}}' in output template '{}'", s));
+        }
+        Ok(OutputTemplate(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_are_substituted() {
+        let template: OutputTemplate = "maze_{w}x{h}_{seed}_{i}.{ext}".parse().unwrap();
+        let fields = TemplateFields {
+            width: 21,
+            height: 15,
+            seed: 7,
+            index: 2,
+            ext: "json",
+        };
+        assert_eq!(template.render(&fields), "maze_21x15_7_2.json");
+        assert!(template.is_unique_per_maze());
+    }
+
+    #[test]
+    fn unknown_placeholders_are_rejected() {
+        let error = "maze_{width}.{ext}".parse::<OutputTemplate>().unwrap_err();
+        assert!(error.contains("{width}"), "{}", error);
+        assert!("maze_{w.json".parse::<OutputTemplate>().is_err());
+        assert!("maze_w}.json".parse::<OutputTemplate>().is_err());
+    }
+}
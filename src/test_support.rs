@@ -0,0 +1,36 @@
+//! Setup shared by the unit tests.
+
+use std::time::Duration;
+
+use crate::{
+    algorithm::GenerationAlgorithm, generate_maze, read_maze, render_maze, retry::RetryPolicy,
+    Maze, SIZE,
+};
+
+/// Parses a maze written out as text.
+pub fn maze(text: &str) -> Maze {
+    read_maze(text.as_bytes()).unwrap()
+}
+
+/// The default-size growing-tree maze for `seed`, rendered as the binary does.
+pub fn generated(seed: u64) -> Maze {
+    render_maze(
+        &generate_maze(GenerationAlgorithm::GrowingTree, SIZE, SIZE, seed),
+        seed,
+    )
+    .unwrap()
+}
+
+/// A path for `name` in the temp directory, unique to this test process.
+pub fn temp_path(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("labyrinthium-{}-{}", std::process::id(), name));
+    path.to_str().unwrap().to_string()
+}
+
+/// Tries every write once, without sleeping.
+pub fn no_retry() -> RetryPolicy {
+    RetryPolicy {
+        attempts: 1,
+        delay: Duration::ZERO,
+    }
+}
@@ -0,0 +1,217 @@
+use serde::Serialize;
+
+use crate::{
+    find_goals, find_start,
+    retry::{write_file, RetryPolicy},
+    Maze,
+};
+
+const TILE_SIZE: usize = 16;
+const WALL_GID: u32 = 1;
+const FLOOR_GID: u32 = 2;
+const START_GID: u32 = 3;
+const GOAL_GID: u32 = 4;
+
+/// The subset of the Tiled JSON map format needed for a single-tileset orthogonal map.
+#[derive(Serialize)]
+struct TiledMap {
+    #[serde(rename = "type")]
+    map_type: &'static str,
+    version: &'static str,
+    orientation: &'static str,
+    renderorder: &'static str,
+    width: usize,
+    height: usize,
+    tilewidth: usize,
+    tileheight: usize,
+    infinite: bool,
+    nextlayerid: u32,
+    nextobjectid: u32,
+    layers: Vec<Layer>,
+    tilesets: Vec<Tileset>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Layer {
+    TileLayer {
+        id: u32,
+        name: &'static str,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        opacity: f32,
+        visible: bool,
+        data: Vec<u32>,
+    },
+    ObjectGroup {
+        id: u32,
+        name: &'static str,
+        x: usize,
+        y: usize,
+        opacity: f32,
+        visible: bool,
+        draworder: &'static str,
+        objects: Vec<Object>,
+    },
+}
+
+#[derive(Serialize)]
+struct Object {
+    id: u32,
+    name: &'static str,
+    #[serde(rename = "type")]
+    object_type: &'static str,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    rotation: f32,
+    visible: bool,
+}
+
+#[derive(Serialize)]
+struct Tileset {
+    firstgid: u32,
+    name: &'static str,
+    image: &'static str,
+    imagewidth: usize,
+    imageheight: usize,
+    tilewidth: usize,
+    tileheight: usize,
+    tilecount: u32,
+    columns: u32,
+    margin: usize,
+    spacing: usize,
+}
+
+/// Writes the maze as a Tiled JSON map. Tiles 1-4 of the `maze-tiles.png` tileset are
+/// wall, floor, start and goal; start and goal are also placed as objects so they can
+/// be picked up by name. Returns the bytes written.
+pub fn create_tiled_file(
+    maze: &Maze,
+    filename: &str,
+    retry: &RetryPolicy,
+) -> std::io::Result<Vec<u8>> {
+    let data = maze
+        .data
+        .iter()
+        .flat_map(|row| {
+            row.iter().map(|&cell| match cell {
+                '#' => WALL_GID,
+                'S' => START_GID,
+                'G' => GOAL_GID,
+                _ => FLOOR_GID,
+            })
+        })
+        .collect();
+
+    let marker = |id, name, (row, col): (usize, usize)| Object {
+        id,
+        name,
+        object_type: name,
+        x: col * TILE_SIZE,
+        y: row * TILE_SIZE,
+        width: TILE_SIZE,
+        height: TILE_SIZE,
+        rotation: 0.0,
+        visible: true,
+    };
+    let mut objects = vec![marker(1, "start", find_start(maze))];
+    for goal in find_goals(maze) {
+        objects.push(marker(objects.len() as u32 + 1, "goal", goal));
+    }
+
+    let map = TiledMap {
+        map_type: "map",
+        version: "1.10",
+        orientation: "orthogonal",
+        renderorder: "right-down",
+        width: maze.cols,
+        height: maze.rows,
+        tilewidth: TILE_SIZE,
+        tileheight: TILE_SIZE,
+        infinite: false,
+        nextlayerid: 3,
+        nextobjectid: objects.len() as u32 + 1,
+        layers: vec![
+            Layer::TileLayer {
+                id: 1,
+                name: "maze",
+                x: 0,
+                y: 0,
+                width: maze.cols,
+                height: maze.rows,
+                opacity: 1.0,
+                visible: true,
+                data,
+            },
+            Layer::ObjectGroup {
+                id: 2,
+                name: "markers",
+                x: 0,
+                y: 0,
+                opacity: 1.0,
+                visible: true,
+                draworder: "topdown",
+                objects,
+            },
+        ],
+        tilesets: vec![Tileset {
+            firstgid: 1,
+            name: "maze",
+            image: "maze-tiles.png",
+            imagewidth: 4 * TILE_SIZE,
+            imageheight: TILE_SIZE,
+            tilewidth: TILE_SIZE,
+            tileheight: TILE_SIZE,
+            tilecount: 4,
+            columns: 4,
+            margin: 0,
+            spacing: 0,
+        }],
+    };
+
+    let json_string = serde_json::to_string_pretty(&map)?;
+    write_file(filename, json_string.as_bytes(), retry)?;
+    Ok(json_string.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        read_maze,
+        test_support::{no_retry, temp_path},
+    };
+
+    #[test]
+    fn tiled_map_has_the_structural_fields() {
+        let maze = read_maze("#######\n#S....#\n#.###.#\n#....G#\n#######\n".as_bytes()).unwrap();
+        let filename = &temp_path("tiled.json");
+        let retry = no_retry();
+        create_tiled_file(&maze, filename, &retry).unwrap();
+
+        let map: Value = serde_json::from_str(&std::fs::read_to_string(filename).unwrap()).unwrap();
+        std::fs::remove_file(filename).unwrap();
+        assert_eq!(map["width"], 7);
+        assert_eq!(map["height"], 5);
+        let layers = map["layers"].as_array().unwrap();
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[0]["type"], "tilelayer");
+        assert_eq!(layers[0]["data"].as_array().unwrap().len(), 7 * 5);
+        assert_eq!(layers[0]["data"][8], START_GID);
+        assert_eq!(layers[1]["type"], "objectgroup");
+        let names: Vec<&str> = layers[1]["objects"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|object| object["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["start", "goal"]);
+    }
+}
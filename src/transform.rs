@@ -0,0 +1,684 @@
+use std::str::FromStr;
+
+use rand::{seq::IndexedRandom, seq::SliceRandom, Rng};
+
+use crate::{error::MazeError, find_goals, find_start, solver::bfs_tree, Maze};
+
+/// Opens up to `ratio` of the interior walls that sit between two open cells on one axis.
+/// Border walls are never touched, and since removals only add connections the maze
+/// stays solvable.
+pub fn open_walls<R: Rng + ?Sized>(maze: &mut Maze, ratio: f64, rng: &mut R) -> usize {
+    let mut candidates = Vec::new();
+    for row in 1..maze.rows.saturating_sub(1) {
+        for col in 1..maze.cols.saturating_sub(1) {
+            if maze.data[row][col] != '#' {
+                continue;
+            }
+            let vertical = maze.data[row - 1][col] != '#' && maze.data[row + 1][col] != '#';
+            let horizontal = maze.data[row][col - 1] != '#' && maze.data[row][col + 1] != '#';
+            if vertical || horizontal {
+                candidates.push((row, col));
+            }
+        }
+    }
+
+    candidates.shuffle(rng);
+    let removed = (candidates.len() as f64 * ratio.clamp(0.0, 1.0)).round() as usize;
+    for &(row, col) in &candidates[..removed] {
+        maze.data[row][col] = '.';
+    }
+    removed
+}
+
+/// Opens `loops` interior walls one at a time, each chosen among walls whose only open
+/// neighbours are two opposite cells, so every removal adds exactly one cycle. Returns
+/// how many loops were added, which is less than requested if candidates run out.
+pub fn add_loops<R: Rng + ?Sized>(maze: &mut Maze, loops: usize, rng: &mut R) -> usize {
+    for added in 0..loops {
+        let mut candidates = Vec::new();
+        for row in 1..maze.rows.saturating_sub(1) {
+            for col in 1..maze.cols.saturating_sub(1) {
+                if maze.data[row][col] != '#' {
+                    continue;
+                }
+                let up = maze.data[row - 1][col] != '#';
+                let down = maze.data[row + 1][col] != '#';
+                let left = maze.data[row][col - 1] != '#';
+                let right = maze.data[row][col + 1] != '#';
+                if (up && down && !left && !right) || (left && right && !up && !down) {
+                    candidates.push((row, col));
+                }
+            }
+        }
+        match candidates.choose(rng) {
+            Some(&(row, col)) => maze.data[row][col] = '.',
+            None => return added,
+        }
+    }
+    loops
+}
+
+/// Braids away dead ends or carves new one-cell spurs, one at a time and chosen at
+/// random, until the maze has `target` dead ends (counted as in
+/// [`crate::stats::compute_stats`]) or no further change is possible. Braiding only
+/// opens walls between two open cells and a spur hangs off a cell that is not a dead
+/// end, so the start and goal stay put and the maze stays solvable. Returns the
+/// achieved count.
+pub fn adjust_dead_ends<R: Rng + ?Sized>(maze: &mut Maze, target: usize, rng: &mut R) -> usize {
+    let directions = [(-1i32, 0i32), (1, 0), (0, -1), (0, 1)];
+    loop {
+        let open = |maze: &Maze, row: i32, col: i32| {
+            row >= 0
+                && col >= 0
+                && (row as usize) < maze.rows
+                && (col as usize) < maze.cols
+                && maze.data[row as usize][col as usize] != '#'
+        };
+        let interior = |maze: &Maze, row: i32, col: i32| {
+            row > 0 && col > 0 && (row as usize) < maze.rows - 1 && (col as usize) < maze.cols - 1
+        };
+        let degree = |maze: &Maze, row: i32, col: i32| {
+            directions
+                .iter()
+                .filter(|&&(dr, dc)| open(maze, row + dr, col + dc))
+                .count()
+        };
+
+        let mut dead_ends = Vec::new();
+        let mut branches = Vec::new();
+        for (row, line) in maze.data.iter().enumerate() {
+            for (col, &cell) in line.iter().enumerate() {
+                let (row, col) = (row as i32, col as i32);
+                if cell == '#' {
+                    continue;
+                }
+                let neighbors = degree(maze, row, col);
+                if neighbors == 1 && cell != 'S' && cell != 'G' {
+                    dead_ends.push((row, col));
+                } else if neighbors >= 2 {
+                    branches.push((row, col));
+                }
+            }
+        }
+        if dead_ends.len() == target {
+            return target;
+        }
+
+        let mut candidates = Vec::new();
+        if dead_ends.len() > target {
+            for &(row, col) in &dead_ends {
+                for &(dr, dc) in &directions {
+                    if !open(maze, row + dr, col + dc)
+                        && interior(maze, row + dr, col + dc)
+                        && open(maze, row + 2 * dr, col + 2 * dc)
+                    {
+                        candidates.push((row + dr, col + dc));
+                    }
+                }
+            }
+        } else {
+            for &(row, col) in &branches {
+                for &(dr, dc) in &directions {
+                    let (spur_row, spur_col) = (row + dr, col + dc);
+                    if !open(maze, spur_row, spur_col)
+                        && interior(maze, spur_row, spur_col)
+                        && degree(maze, spur_row, spur_col) == 1
+                    {
+                        candidates.push((spur_row, spur_col));
+                    }
+                }
+            }
+        }
+
+        match candidates.choose(rng) {
+            Some(&(row, col)) => maze.data[row as usize][col as usize] = '.',
+            None => return dead_ends.len(),
+        }
+    }
+}
+
+/// Opens walls or fills dead ends, one random cell at a time, until the maze has
+/// within `tolerance` of `target` open cells. Walls are only opened between two open
+/// cells on one axis and only dead ends other than the start and goal are filled, so
+/// the maze stays solvable; the lowest reachable count is the through route and the
+/// highest a fully opened interior. Returns the achieved count.
+pub fn open_to_count<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    target: usize,
+    tolerance: usize,
+    rng: &mut R,
+) -> Result<usize, MazeError> {
+    loop {
+        let count = maze.open_cell_count();
+        if count.abs_diff(target) <= tolerance {
+            return Ok(count);
+        }
+
+        let mut candidates = Vec::new();
+        for row in 1..maze.rows.saturating_sub(1) {
+            for col in 1..maze.cols.saturating_sub(1) {
+                let cell = maze.data[row][col];
+                let up = maze.data[row - 1][col] != '#';
+                let down = maze.data[row + 1][col] != '#';
+                let left = maze.data[row][col - 1] != '#';
+                let right = maze.data[row][col + 1] != '#';
+                let eligible = if count < target {
+                    cell == '#' && ((up && down) || (left && right))
+                } else {
+                    cell == '.' && [up, down, left, right].iter().filter(|&&open| open).count() <= 1
+                };
+                if eligible {
+                    candidates.push((row, col));
+                }
+            }
+        }
+
+        match candidates.choose(rng) {
+            Some(&(row, col)) => {
+                maze.data[row][col] = if count < target { '.' } else { '#' };
+            }
+            None => {
+                return Err(MazeError::OpenCellTargetMissed {
+                    target,
+                    achieved: count,
+                })
+            }
+        }
+    }
+}
+
+/// Centres the maze on a `width` x `height` canvas filled with walls.
+pub fn pad_to(maze: &Maze, width: usize, height: usize) -> Result<Maze, MazeError> {
+    if maze.cols > width || maze.rows > height {
+        return Err(MazeError::CanvasTooSmall {
+            maze: (maze.cols, maze.rows),
+            canvas: (width, height),
+        });
+    }
+
+    let left = (width - maze.cols) / 2;
+    let top = (height - maze.rows) / 2;
+    let mut data = vec![vec!['#'; width]; height];
+    for (row, line) in maze.data.iter().enumerate() {
+        data[top + row][left..left + line.len()].copy_from_slice(line);
+    }
+
+    Ok(Maze {
+        rows: height,
+        cols: width,
+        data,
+    })
+}
+
+/// Carves one interior wall next to the start and each goal that have no open
+/// neighbour, choosing a wall with an open cell behind it so the endpoint joins the maze.
+/// Returns how many walls were carved, or [`MazeError::IsolatedStart`] /
+/// [`MazeError::IsolatedGoal`] for an endpoint that no single carve can join; the outer
+/// border is never opened.
+pub fn connect_endpoints(maze: &mut Maze) -> Result<usize, MazeError> {
+    let directions = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+    let start = find_start(maze);
+    let mut endpoints = find_goals(maze);
+    endpoints.insert(0, start);
+    let (rows, cols) = (maze.rows as i32, maze.cols as i32);
+    let interior = |r: i32, c: i32| r > 0 && c > 0 && r < rows - 1 && c < cols - 1;
+    let mut carved = 0;
+
+    for (row, col) in endpoints {
+        let cell = |dr: i32, dc: i32| {
+            let (r, c) = (row as i32 + dr, col as i32 + dc);
+            if r < 0 || c < 0 {
+                return None;
+            }
+            maze.data.get(r as usize)?.get(c as usize).copied()
+        };
+        if directions
+            .iter()
+            .any(|&(dr, dc)| cell(dr, dc).is_some_and(|c| c != '#'))
+        {
+            continue;
+        }
+        let wall = directions.iter().copied().find(|&(dr, dc)| {
+            interior(row as i32 + dr, col as i32 + dc)
+                && cell(2 * dr, 2 * dc).is_some_and(|c| c != '#')
+        });
+        let Some((dr, dc)) = wall else {
+            return Err(if (row, col) == start {
+                MazeError::IsolatedStart { row, col }
+            } else {
+                MazeError::IsolatedGoal { row, col }
+            });
+        };
+        maze.data[(row as i32 + dr) as usize][(col as i32 + dc) as usize] = '.';
+        carved += 1;
+    }
+    Ok(carved)
+}
+
+/// Where [`place_goal`] moves the goal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GoalPlacement {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// The cell closest to the geometric centre of the grid.
+    Center,
+    /// A cell drawn with the maze's own random generator.
+    Random,
+}
+
+impl FromStr for GoalPlacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "top-left" => Ok(GoalPlacement::TopLeft),
+            "top-right" => Ok(GoalPlacement::TopRight),
+            "bottom-left" => Ok(GoalPlacement::BottomLeft),
+            "bottom-right" => Ok(GoalPlacement::BottomRight),
+            "center" => Ok(GoalPlacement::Center),
+            "random" => Ok(GoalPlacement::Random),
+            _ => Err(format!(
+                "invalid goal placement '{}': expected top-left, top-right, bottom-left, \
+                 bottom-right, center or random",
+                s
+            )),
+        }
+    }
+}
+
+/// Replaces every goal with a single one at `placement`, on the open cell reachable
+/// from the start that is nearest the target point (by Manhattan distance, ties in
+/// row-major order). Returns the new goal.
+pub fn place_goal<R: Rng + ?Sized>(
+    maze: &mut Maze,
+    placement: GoalPlacement,
+    rng: &mut R,
+) -> Result<(usize, usize), MazeError> {
+    for (row, col) in find_goals(maze) {
+        maze.data[row][col] = '.';
+    }
+    let start = find_start(maze);
+    let mut candidates: Vec<_> = bfs_tree(maze, start)
+        .distances
+        .into_keys()
+        .filter(|&cell| cell != start)
+        .collect();
+    candidates.sort();
+
+    // Doubled coordinates, so the centre of an even-sized grid stays integral.
+    let (bottom, right) = (2 * (maze.rows.max(1) - 1), 2 * (maze.cols.max(1) - 1));
+    let target = match placement {
+        GoalPlacement::TopLeft => Some((0, 0)),
+        GoalPlacement::TopRight => Some((0, right)),
+        GoalPlacement::BottomLeft => Some((bottom, 0)),
+        GoalPlacement::BottomRight => Some((bottom, right)),
+        GoalPlacement::Center => Some((bottom / 2, right / 2)),
+        GoalPlacement::Random => None,
+    };
+    let goal = match target {
+        Some((row, col)) => candidates
+            .into_iter()
+            .min_by_key(|&(r, c)| (2 * r).abs_diff(row) + (2 * c).abs_diff(col)),
+        None => candidates.choose(rng).copied(),
+    }
+    .ok_or(MazeError::NoOpenCells)?;
+    maze.data[goal.0][goal.1] = 'G';
+    Ok(goal)
+}
+
+/// Gives every plain floor cell a random weight from 1 to `max_weight` (at most 9),
+/// written as its digit; weight 1 stays `.`.
+pub fn assign_weights<R: Rng + ?Sized>(maze: &mut Maze, max_weight: u32, rng: &mut R) {
+    let max_weight = max_weight.clamp(1, 9);
+    for cell in maze.data.iter_mut().flatten() {
+        if *cell == '.' {
+            let weight = rng.random_range(1..=max_weight);
+            if weight > 1 {
+                *cell = char::from_digit(weight, 10).unwrap();
+            }
+        }
+    }
+}
+
+impl Maze {
+    /// Expands every cell into a `factor` x `factor` block of the same cell, so corridors
+    /// and walls widen evenly and the topology is unchanged. The start and goals keep
+    /// only the middle cell of their block, the rest becoming floor. A shortest path
+    /// between them grows to at most `factor` times its length; it falls short of that
+    /// only where the wider corridors let it cut the inside of a turn. A factor of 0 is
+    /// treated as 1.
+    pub fn upscale(&self, factor: usize) -> Maze {
+        let factor = factor.max(1);
+        let mut data: Vec<Vec<char>> = self
+            .data
+            .iter()
+            .flat_map(|line| {
+                let wide: Vec<char> = line
+                    .iter()
+                    .flat_map(|&cell| {
+                        let cell = if matches!(cell, 'S' | 'G') { '.' } else { cell };
+                        std::iter::repeat_n(cell, factor)
+                    })
+                    .collect();
+                std::iter::repeat_n(wide, factor)
+            })
+            .collect();
+        for (row, line) in self.data.iter().enumerate() {
+            for (col, &cell) in line.iter().enumerate() {
+                if matches!(cell, 'S' | 'G') {
+                    data[row * factor + factor / 2][col * factor + factor / 2] = cell;
+                }
+            }
+        }
+
+        Maze {
+            rows: self.rows * factor,
+            cols: self.cols * factor,
+            data,
+        }
+    }
+
+    /// Swaps walls and open cells. The start moves to the nearest cell (by Manhattan
+    /// distance, ties in row-major order) that is open after the swap, and each goal to
+    /// the nearest free cell connected to the new start, since the old solution path
+    /// splits the former walls into separate regions.
+    pub fn invert(&self) -> Result<Maze, MazeError> {
+        let mut inverted = Maze {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .iter()
+                .map(|line| {
+                    line.iter()
+                        .map(|&cell| if cell == '#' { '.' } else { '#' })
+                        .collect()
+                })
+                .collect(),
+        };
+
+        let open_cells: Vec<_> = inverted
+            .data
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.iter()
+                    .enumerate()
+                    .filter(|(_, &cell)| cell == '.')
+                    .map(move |(col, _)| (row, col))
+            })
+            .collect();
+        let nearest = |cells: &mut dyn Iterator<Item = (usize, usize)>,
+                       (row, col): (usize, usize)| {
+            cells
+                .min_by_key(|&(r, c): &(usize, usize)| r.abs_diff(row) + c.abs_diff(col))
+                .ok_or(MazeError::NoOpenCells)
+        };
+
+        let start = nearest(&mut open_cells.iter().copied(), find_start(self))?;
+        inverted.data[start.0][start.1] = 'S';
+        let mut reachable: Vec<_> = bfs_tree(&inverted, start).distances.into_keys().collect();
+        reachable.sort();
+        for goal in find_goals(self) {
+            let free = &mut reachable
+                .iter()
+                .copied()
+                .filter(|&(row, col)| inverted.data[row][col] == '.');
+            let target = nearest(free, goal)?;
+            inverted.data[target.0][target.1] = 'G';
+        }
+
+        Ok(inverted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        read_maze,
+        solver::{check_endpoints, Bfs, PathFinder},
+        test_support::generated,
+    };
+
+    #[test]
+    fn higher_openness_shortens_the_average_solution() {
+        let average_length = |ratio: f64| {
+            let total: usize = (0..10)
+                .map(|seed| {
+                    let mut maze = generated(seed);
+                    open_walls(&mut maze, ratio, &mut StdRng::seed_from_u64(seed));
+                    Bfs.find_path(&maze).unwrap().length
+                })
+                .sum();
+            total as f64 / 10.0
+        };
+        let closed = average_length(0.0);
+        let half = average_length(0.5);
+        let open = average_length(1.0);
+        assert!(half < closed, "{} !< {}", half, closed);
+        assert!(open < half, "{} !< {}", open, half);
+    }
+
+    #[test]
+    fn added_loops_are_the_extra_edges_beyond_a_tree() {
+        let mut maze = generated(4);
+        assert_eq!(add_loops(&mut maze, 5, &mut StdRng::seed_from_u64(4)), 5);
+
+        let open = |row: usize, col: usize| maze.data[row][col] != '#';
+        let nodes = maze.open_cell_count();
+        let mut edges = 0;
+        for row in 0..maze.rows {
+            for col in 0..maze.cols {
+                if open(row, col) {
+                    edges += usize::from(row + 1 < maze.rows && open(row + 1, col));
+                    edges += usize::from(col + 1 < maze.cols && open(row, col + 1));
+                }
+            }
+        }
+        assert_eq!(edges, nodes - 1 + 5);
+        assert_eq!(crate::stats::loop_count(&maze), 5);
+    }
+
+    #[test]
+    fn padding_reaches_the_canvas_and_stays_solvable() {
+        let maze = generated(5);
+        let padded = pad_to(&maze, 30, 26).unwrap();
+        assert_eq!((padded.cols, padded.rows), (30, 26));
+        assert_eq!(padded.data.len(), 26);
+        assert!(padded.data.iter().all(|line| line.len() == 30));
+        assert_eq!(
+            Bfs.find_path(&padded).unwrap().length,
+            Bfs.find_path(&maze).unwrap().length
+        );
+        assert!(matches!(
+            pad_to(&maze, 20, 26),
+            Err(MazeError::CanvasTooSmall { .. })
+        ));
+    }
+
+    #[test]
+    fn walled_in_endpoints_are_detected() {
+        let start = read_maze("#######\n#S#..G#\n#######\n".as_bytes()).unwrap();
+        assert!(matches!(
+            check_endpoints(&start),
+            Err(MazeError::IsolatedStart { row: 1, col: 1 })
+        ));
+        let goal = read_maze("#######\n#S..#G#\n#######\n".as_bytes()).unwrap();
+        assert!(matches!(
+            check_endpoints(&goal),
+            Err(MazeError::IsolatedGoal { row: 1, col: 5 })
+        ));
+    }
+
+    #[test]
+    fn fixing_carves_one_wall_to_join_the_maze() {
+        let mut maze = read_maze("#######\n#S..#G#\n#######\n".as_bytes()).unwrap();
+        assert_eq!(connect_endpoints(&mut maze).unwrap(), 1);
+        assert_eq!(maze.data[1][4], '.');
+        assert!(check_endpoints(&maze).is_ok());
+        assert_eq!(Bfs.find_path(&maze).unwrap().length, 5);
+        assert_eq!(connect_endpoints(&mut maze).unwrap(), 0);
+    }
+
+    #[test]
+    fn an_endpoint_no_interior_carve_can_join_is_reported() {
+        // Each endpoint has the border on three sides and two walls on the fourth.
+        let text = "#######\n#S##.G#\n#######\n";
+        let mut start = read_maze(text.as_bytes()).unwrap();
+        assert!(matches!(
+            connect_endpoints(&mut start),
+            Err(MazeError::IsolatedStart { row: 1, col: 1 })
+        ));
+        assert_eq!(start.to_string(), text);
+
+        let text = "#######\n#S.##G#\n#######\n";
+        let mut goal = read_maze(text.as_bytes()).unwrap();
+        assert!(matches!(
+            connect_endpoints(&mut goal),
+            Err(MazeError::IsolatedGoal { row: 1, col: 5 })
+        ));
+        assert_eq!(goal.to_string(), text);
+    }
+
+    #[test]
+    fn dead_ends_land_close_to_the_target() {
+        let dead_ends = |maze: &Maze| {
+            let path = Bfs.find_path(maze).unwrap().into_path();
+            crate::stats::compute_stats(maze, &path).dead_ends
+        };
+        let original = generated(7);
+        let before = dead_ends(&original);
+        for target in [before / 2, before + 10] {
+            let mut maze = generated(7);
+            let achieved = adjust_dead_ends(&mut maze, target, &mut StdRng::seed_from_u64(7));
+            assert!(
+                achieved.abs_diff(target) <= 1,
+                "{} for {}",
+                achieved,
+                target
+            );
+            assert_eq!(dead_ends(&maze), achieved);
+            assert_eq!(find_start(&maze), find_start(&original));
+            assert_eq!(find_goals(&maze), find_goals(&original));
+        }
+    }
+
+    #[test]
+    fn inverting_swaps_the_wall_and_floor_counts() {
+        let maze = generated(154);
+        let walls = |maze: &Maze| maze.rows * maze.cols - maze.open_cell_count();
+        let inverted = maze.invert().unwrap();
+        assert_eq!(inverted.open_cell_count(), walls(&maze));
+        assert_eq!(walls(&inverted), maze.open_cell_count());
+
+        let count = |marker: char| {
+            inverted
+                .data
+                .iter()
+                .flatten()
+                .filter(|&&c| c == marker)
+                .count()
+        };
+        assert_eq!((count('S'), count('G')), (1, 1));
+        let (row, col) = find_start(&inverted);
+        assert_eq!(maze.data[row][col], '#');
+        assert!(Bfs.find_path(&inverted).is_some());
+    }
+
+    #[test]
+    fn open_cell_targets_are_met_within_tolerance() {
+        let original = generated(171).open_cell_count();
+        for target in [original - 40, original + 40] {
+            let mut maze = generated(171);
+            let achieved =
+                open_to_count(&mut maze, target, 2, &mut StdRng::seed_from_u64(171)).unwrap();
+            assert!(
+                achieved.abs_diff(target) <= 2,
+                "{} for {}",
+                achieved,
+                target
+            );
+            assert_eq!(maze.open_cell_count(), achieved);
+            assert!(Bfs.find_path(&maze).is_some());
+        }
+        assert!(matches!(
+            open_to_count(
+                &mut generated(171),
+                10_000,
+                2,
+                &mut StdRng::seed_from_u64(171)
+            ),
+            Err(MazeError::OpenCellTargetMissed { target: 10_000, .. })
+        ));
+    }
+
+    #[test]
+    fn goals_land_at_their_placement() {
+        let room = || {
+            read_maze("#######\n#S....#\n#.....#\n#.....#\n#....G#\n#######\n".as_bytes()).unwrap()
+        };
+        let mut rng = StdRng::seed_from_u64(175);
+        for (placement, expected) in [
+            // (1, 1) is the start, so the nearest free cell wins, ties in row-major order.
+            (GoalPlacement::TopLeft, (1, 2)),
+            (GoalPlacement::TopRight, (1, 5)),
+            (GoalPlacement::BottomLeft, (4, 1)),
+            (GoalPlacement::BottomRight, (4, 5)),
+            (GoalPlacement::Center, (2, 3)),
+        ] {
+            let mut maze = room();
+            assert_eq!(
+                place_goal(&mut maze, placement, &mut rng).unwrap(),
+                expected
+            );
+            assert_eq!(find_goals(&maze), [expected]);
+        }
+
+        let random = |seed| {
+            let mut maze = room();
+            place_goal(
+                &mut maze,
+                GoalPlacement::Random,
+                &mut StdRng::seed_from_u64(seed),
+            )
+            .unwrap()
+        };
+        let goal = random(175);
+        assert_eq!(goal, random(175));
+        assert!(goal != (1, 1) && room().data[goal.0][goal.1] != '#');
+    }
+
+    #[test]
+    fn upscaled_mazes_stay_solvable_with_scaled_paths() {
+        let corridor = read_maze("#######\n#S...G#\n#######\n".as_bytes()).unwrap();
+        let steps = |maze: &Maze| Bfs.find_path(maze).unwrap().length - 1;
+        let zoomed = corridor.upscale(3);
+        assert_eq!((zoomed.rows, zoomed.cols), (9, 21));
+        assert_eq!(steps(&zoomed), 3 * steps(&corridor));
+
+        let maze = generated(183);
+        for factor in [2, 3] {
+            let zoomed = maze.upscale(factor);
+            let (original, scaled) = (steps(&maze), steps(&zoomed));
+            assert!(
+                scaled <= factor * original,
+                "{} > {} x {}",
+                scaled,
+                factor,
+                original
+            );
+            assert!(
+                scaled > (factor - 1) * original,
+                "{} <= {} x {}",
+                scaled,
+                factor - 1,
+                original
+            );
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use serde::Serialize;
+
+use crate::Maze;
+
+/// An axis-aligned block of wall cells; `x` is the left column and `y` the top row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Covers every wall cell with non-overlapping rectangles, e.g. as colliders. Scanning
+/// row by row, each uncovered wall starts a rectangle that grows right as far as the
+/// walls go and then down while the whole span below is still uncovered wall.
+pub fn wall_rects(maze: &Maze) -> Vec<Rect> {
+    let mut covered: Vec<Vec<bool>> = maze
+        .data
+        .iter()
+        .map(|line| line.iter().map(|&c| c != '#').collect())
+        .collect();
+    let free = |covered: &[Vec<bool>], row: usize, col: usize| {
+        covered
+            .get(row)
+            .and_then(|line| line.get(col))
+            .is_some_and(|&done| !done)
+    };
+    let mut rects = Vec::new();
+
+    for row in 0..covered.len() {
+        for col in 0..covered[row].len() {
+            if !free(&covered, row, col) {
+                continue;
+            }
+            let width = (col..).take_while(|&c| free(&covered, row, c)).count();
+            let height = (row..)
+                .take_while(|&r| (col..col + width).all(|c| free(&covered, r, c)))
+                .count();
+            for line in &mut covered[row..row + height] {
+                line[col..col + width].fill(true);
+            }
+            rects.push(Rect {
+                x: col,
+                y: row,
+                width,
+                height,
+            });
+        }
+    }
+    rects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::generated;
+
+    #[test]
+    fn rects_cover_each_wall_cell_exactly_once() {
+        let maze = generated(181);
+        let rects = wall_rects(&maze);
+        let walls = maze.rows * maze.cols - maze.open_cell_count();
+        let area: usize = rects.iter().map(|rect| rect.width * rect.height).sum();
+        assert_eq!(area, walls);
+
+        let mut covers = vec![vec![0; maze.cols]; maze.rows];
+        for rect in &rects {
+            for line in &mut covers[rect.y..rect.y + rect.height] {
+                for count in &mut line[rect.x..rect.x + rect.width] {
+                    *count += 1;
+                }
+            }
+        }
+        for (row, line) in maze.data.iter().enumerate() {
+            for (col, &cell) in line.iter().enumerate() {
+                assert_eq!(covers[row][col], usize::from(cell == '#'));
+            }
+        }
+        assert!(rects.len() < walls / 2);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    algorithm::GenerationAlgorithm, error::MazeError, generate_maze, maze_json_string, render_maze,
+    solve_maze, solver::validate_solution, JsonOptions, SIZE,
+};
+
+/// Everything [`generate_maze_json_string`] needs to reproduce a maze.
+pub struct MazeConfig {
+    pub seed: u64,
+    pub algorithm: GenerationAlgorithm,
+    pub json: JsonOptions,
+}
+
+/// Generates, solves and serializes a maze entirely in memory, returning the same JSON
+/// the CLI writes to disk. Nothing touches the filesystem, so it can run under WASM.
+pub fn generate_maze_json_string(config: &MazeConfig) -> Result<String, MazeError> {
+    let maze = render_maze(
+        &generate_maze(config.algorithm, SIZE, SIZE, config.seed),
+        config.seed,
+    )?;
+    let solution = solve_maze(&maze).ok_or(MazeError::NoSolution)?;
+    validate_solution(&maze, &solution)?;
+    maze_json_string(maze.cols, maze.rows, &maze, &solution, &config.json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_returned_string_is_the_maze_json() {
+        let config = MazeConfig {
+            seed: 134,
+            algorithm: GenerationAlgorithm::GrowingTree,
+            json: JsonOptions::default(),
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&generate_maze_json_string(&config).unwrap()).unwrap();
+        assert_eq!(
+            (json["width"].as_u64(), json["height"].as_u64()),
+            (Some(21), Some(21))
+        );
+        assert_eq!(json["maze"].as_array().unwrap().len(), 21 * 21);
+        assert!(json["solution"].as_array().unwrap().len() > 1);
+        assert_eq!(
+            generate_maze_json_string(&config).unwrap(),
+            generate_maze_json_string(&config).unwrap()
+        );
+    }
+}
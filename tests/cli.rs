@@ -0,0 +1,461 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// A fresh, empty working directory for one test.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("labyrinthium-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs the binary in `dir` with no `LAB_*` or `RUST_LOG` variables from the outside.
+fn run(dir: &Path, args: &[&str], envs: &[(&str, &str)]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_labyrinthium_generator"));
+    command.args(args).current_dir(dir).env_remove("RUST_LOG");
+    for (key, _) in env::vars().filter(|(key, _)| key.starts_with("LAB_")) {
+        command.env_remove(key);
+    }
+    command.envs(envs.iter().copied()).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+/// The `key=value` lines of `--stats-only` output.
+fn parse_stats(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+#[test]
+fn stats_only_prints_key_value_metrics_and_writes_nothing() {
+    let dir = scratch_dir("stats-only");
+    let output = run(&dir, &["--stats-only", "--seed", "1"], &[]);
+    assert!(output.status.success());
+
+    let stats = parse_stats(&stdout(&output));
+    for key in ["path_length", "dead_ends", "junctions"] {
+        assert!(
+            stats[key].parse::<usize>().is_ok(),
+            "{} is not a count",
+            key
+        );
+    }
+    for key in ["wall_density", "difficulty"] {
+        assert!(stats[key].parse::<f64>().is_ok(), "{} is not a number", key);
+    }
+    assert!(stats["path_length"].parse::<usize>().unwrap() > 1);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+    let output = run(&dir, &["--stats-only", "--bundle", "b.jsonl"], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+fn manifest(dir: &Path) -> Vec<serde_json::Value> {
+    let text = fs::read_to_string(dir.join("manifest.json")).unwrap();
+    serde_json::from_str(&text).unwrap()
+}
+
+#[test]
+fn manifest_has_one_entry_per_generated_maze() {
+    let dir = scratch_dir("manifest");
+    let args = [
+        "--count",
+        "3",
+        "--seed",
+        "10",
+        "--out-dir",
+        ".",
+        "--manifest",
+        "manifest.json",
+    ];
+    assert!(run(&dir, &args, &[]).status.success());
+
+    let entries = manifest(&dir);
+    assert_eq!(entries.len(), 3);
+    for (entry, seed) in entries.iter().zip(10..) {
+        assert_eq!(entry["seed"], seed);
+        assert_eq!(entry["algorithm"], "growing-tree");
+        assert_eq!(
+            (entry["width"].as_u64(), entry["height"].as_u64()),
+            (Some(21), Some(21))
+        );
+        assert_eq!(entry["content_hash"].as_str().unwrap().len(), 16);
+        assert!(dir.join(entry["file"].as_str().unwrap()).exists());
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn with_logging_off_stdout_holds_only_the_maze() {
+    let dir = scratch_dir("logging");
+    let args = ["--seed", "1", "--out-dir", "."];
+    let output = run(&dir, &args, &[("RUST_LOG", "off")]);
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+
+    let text = stdout(&output);
+    let mut lines = text.lines();
+    assert_eq!(lines.next(), Some("Original maze:"));
+    let rows: Vec<&str> = lines.collect();
+    assert_eq!(rows.len(), 21);
+    assert!(rows
+        .iter()
+        .all(|row| row.len() == 21 && row.chars().all(|c| "#.SG".contains(c))));
+
+    let logged = run(&dir, &args, &[]);
+    assert_eq!(logged.stdout, output.stdout);
+    assert!(String::from_utf8(logged.stderr).unwrap().contains("INFO"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn an_out_of_range_open_density_logs_a_warning() {
+    let dir = scratch_dir("density");
+    let warning = |args: &[&str]| {
+        let output = run(&dir, args, &[("RUST_LOG", "warn")]);
+        assert!(output.status.success());
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("Open density")
+    };
+    assert!(!warning(&["--seed", "1", "--out-dir", "."]));
+    assert!(warning(&[
+        "--seed",
+        "1",
+        "--out-dir",
+        ".",
+        "--min-open-density",
+        "0.9"
+    ]));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn version_and_help_exit_zero_and_unknown_flags_exit_two() {
+    let dir = scratch_dir("version");
+    let version = run(&dir, &["--version"], &[]);
+    assert_eq!(version.status.code(), Some(0));
+    assert!(stdout(&version).contains(env!("CARGO_PKG_VERSION")));
+
+    let help = run(&dir, &["--help"], &[]);
+    assert_eq!(help.status.code(), Some(0));
+    for flag in ["--seed", "--count", "--stats-only", "--version"] {
+        assert!(
+            stdout(&help).contains(flag),
+            "--help does not list {}",
+            flag
+        );
+    }
+
+    let unknown = run(&dir, &["--no-such-flag"], &[]);
+    assert_eq!(unknown.status.code(), Some(2));
+    assert!(String::from_utf8(unknown.stderr)
+        .unwrap()
+        .contains("--no-such-flag"));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn profile_has_every_phase_in_nanoseconds() {
+    let dir = scratch_dir("profile");
+    let args = ["--seed", "1", "--out-dir", ".", "--profile", "profile.json"];
+    assert!(run(&dir, &args, &[]).status.success());
+
+    let text = fs::read_to_string(dir.join("profile.json")).unwrap();
+    let profile: serde_json::Value = serde_json::from_str(&text).unwrap();
+    for phase in ["read", "generate", "solve", "serialize"] {
+        assert!(
+            profile[phase].is_u64(),
+            "{} is not a nanosecond count",
+            phase
+        );
+    }
+    assert!(profile["generate"].as_u64().unwrap() > 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn output_template_names_every_maze_of_a_batch() {
+    let dir = scratch_dir("template");
+    let args = [
+        "--count",
+        "3",
+        "--seed",
+        "5",
+        "--out-dir",
+        ".",
+        "--output-template",
+        "maze_{w}x{h}_{seed}_{i}.{ext}",
+    ];
+    assert!(run(&dir, &args, &[]).status.success());
+
+    let mut names: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "maze_21x21_5_0.json",
+            "maze_21x21_6_1.json",
+            "maze_21x21_7_2.json"
+        ]
+    );
+
+    let output = run(&dir, &["--output-template", "maze_{size}.json"], &[]);
+    assert_eq!(output.status.code(), Some(2));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn seed_file_replays_the_mazes_of_single_seed_runs() {
+    let dir = scratch_dir("seed-file");
+    let seeds = [42, 7, 1000];
+    let mut expected = Vec::new();
+    for seed in seeds {
+        let args = ["--seed", &seed.to_string(), "--out-dir", "."];
+        assert!(run(&dir, &args, &[]).status.success());
+        expected.push(fs::read(dir.join("maze-21x21.json")).unwrap());
+    }
+
+    fs::write(dir.join("seeds.txt"), "42\n7\n1000\n").unwrap();
+    let args = ["--seed-file", "seeds.txt", "--count", "5", "--out-dir", "."];
+    assert!(run(&dir, &args, &[]).status.success());
+    for (index, bytes) in expected.iter().enumerate() {
+        let replayed = fs::read(dir.join(format!("maze-21x21-{}.json", index))).unwrap();
+        assert_eq!(
+            &replayed, bytes,
+            "maze {} differs from its single-seed run",
+            index
+        );
+    }
+    assert!(!dir.join("maze-21x21-3.json").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn min_and_max_path_bound_the_solution_length() {
+    let dir = scratch_dir("path-bounds");
+    for seed in ["1", "2", "3"] {
+        let args = [
+            "--stats-only",
+            "--seed",
+            seed,
+            "--min-path",
+            "40",
+            "--max-path",
+            "50",
+        ];
+        let output = run(&dir, &args, &[]);
+        assert!(output.status.success());
+        let length: usize = parse_stats(&stdout(&output))["path_length"]
+            .parse()
+            .unwrap();
+        assert!((40..=50).contains(&length), "seed {} gave {}", seed, length);
+    }
+
+    let args = [
+        "--stats-only",
+        "--min-path",
+        "50",
+        "--max-path",
+        "40",
+        "--path-attempts",
+        "5",
+    ];
+    let output = run(&dir, &args, &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("after 5 attempts"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_failed_maze_is_recorded_and_the_rest_are_written() {
+    let dir = scratch_dir("partial");
+    fs::write(dir.join("seeds.txt"), "1\n2\n3\n").unwrap();
+    // Seed 2's solution is 55 cells long, over --max-path, so it fails on its only attempt.
+    let args = [
+        "--seed-file",
+        "seeds.txt",
+        "--max-path",
+        "53",
+        "--path-attempts",
+        "1",
+        "--out-dir",
+        ".",
+        "--manifest",
+        "manifest.json",
+    ];
+    let output = run(&dir, &args, &[]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let entries = manifest(&dir);
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[1]["seed"], 2);
+    assert!(entries[1]["error"]
+        .as_str()
+        .unwrap()
+        .contains("path length"));
+    assert!(entries[1].get("file").is_none());
+    for entry in [&entries[0], &entries[2]] {
+        assert!(dir.join(entry["file"].as_str().unwrap()).exists());
+    }
+    assert!(!dir.join("maze-21x21-1.json").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn environment_variables_fill_in_for_missing_flags() {
+    let dir = scratch_dir("env");
+    fs::create_dir_all(dir.join("from-env")).unwrap();
+    fs::create_dir_all(dir.join("from-flags")).unwrap();
+    let envs = [
+        ("LAB_SEED", "5"),
+        ("LAB_COUNT", "2"),
+        ("LAB_OUT_DIR", "from-env"),
+        ("LAB_FORMAT", "grid"),
+    ];
+    assert!(run(&dir, &[], &envs).status.success());
+    let args = [
+        "--seed",
+        "5",
+        "--count",
+        "2",
+        "--out-dir",
+        "from-flags",
+        "--format",
+        "grid",
+    ];
+    assert!(run(&dir, &args, &[]).status.success());
+    for name in ["maze-21x21.grid-0.json", "maze-21x21.grid-1.json"] {
+        assert_eq!(
+            fs::read(dir.join("from-env").join(name)).unwrap(),
+            fs::read(dir.join("from-flags").join(name)).unwrap()
+        );
+    }
+
+    // A flag wins over its variable.
+    let args = ["--seed", "6", "--count", "1", "--out-dir", "from-flags"];
+    assert!(run(&dir, &args, &envs).status.success());
+    let seed_six = fs::read(dir.join("from-flags/maze-21x21.grid.json")).unwrap();
+    assert_ne!(
+        seed_six,
+        fs::read(dir.join("from-env/maze-21x21.grid-0.json")).unwrap()
+    );
+
+    // The room counts change the grid, and with it the default file name.
+    fs::create_dir_all(dir.join("sized")).unwrap();
+    let envs = [
+        ("LAB_SEED", "5"),
+        ("LAB_WIDTH", "6"),
+        ("LAB_HEIGHT", "4"),
+        ("LAB_OUT_DIR", "sized"),
+    ];
+    assert!(run(&dir, &[], &envs).status.success());
+    let sized: serde_json::Value =
+        serde_json::from_slice(&fs::read(dir.join("sized/maze-13x9.json")).unwrap()).unwrap();
+    assert_eq!(
+        (sized["width"].as_u64(), sized["height"].as_u64()),
+        (Some(13), Some(9))
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Rows of the `--compare-solvers` table by solver: length, expanded cells if the solver
+/// reports them and whether the path is a shortest one.
+fn parse_comparison(text: &str) -> HashMap<String, (usize, Option<usize>, bool)> {
+    text.lines()
+        .skip_while(|line| !line.starts_with("solver"))
+        .skip(1)
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (length, expanded) = (fields[1].parse().unwrap(), fields[2].parse().ok());
+            (
+                fields[0].to_string(),
+                (length, expanded, fields[3] == "yes"),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn compared_shortest_path_solvers_agree_on_length() {
+    let dir = scratch_dir("compare");
+    let args = [
+        "--compare-solvers",
+        "--seed",
+        "3",
+        "--loops",
+        "20",
+        "--out-dir",
+        ".",
+    ];
+    let output = run(&dir, &args, &[("RUST_LOG", "off")]);
+    assert!(output.status.success());
+
+    let table = parse_comparison(&stdout(&output));
+    let (shortest, _, _) = table["bfs"];
+    for (solver, &(length, _, is_shortest)) in &table {
+        if solver != "dfs" {
+            assert_eq!(length, shortest, "{} disagrees with bfs", solver);
+            assert!(is_shortest);
+        }
+    }
+    assert!(["bfs", "astar", "dijkstra"]
+        .iter()
+        .all(|solver| table.contains_key(*solver)));
+    // With loops the first path DFS finds here is a detour.
+    let (dfs, _, dfs_shortest) = table["dfs"];
+    assert!(dfs > shortest && !dfs_shortest);
+    assert!(table["astar"].1 < table["bfs"].1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn legacy_coords_writes_the_same_coordinates_as_the_default() {
+    let dir = scratch_dir("legacy-coords");
+    fs::create_dir_all(dir.join("legacy")).unwrap();
+    fs::create_dir_all(dir.join("corrected")).unwrap();
+    let legacy = ["--seed", "113", "--out-dir", "legacy", "--legacy-coords"];
+    assert!(run(&dir, &legacy, &[]).status.success());
+    assert!(run(&dir, &["--seed", "113", "--out-dir", "corrected"], &[])
+        .status
+        .success());
+
+    let read = |sub: &str| -> serde_json::Value {
+        serde_json::from_slice(&fs::read(dir.join(sub).join("maze-21x21.json")).unwrap()).unwrap()
+    };
+    let (legacy, corrected) = (read("legacy"), read("corrected"));
+    for key in ["start", "goal", "solution"] {
+        assert_eq!(legacy[key], corrected[key], "{} differs", key);
+    }
+    // This goal is an exit in the bottom wall: x is its column and y the last row.
+    assert_eq!(corrected["goal"], serde_json::json!({"x": 1, "y": 20}));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn open_cells_beyond_the_grid_need_a_larger_maze() {
+    let dir = scratch_dir("open-cells");
+    let args = ["--stats-only", "--seed", "1", "--open-cells", "500"];
+    // A 10x10-room grid has 19 * 19 interior cells plus its two exits.
+    assert_eq!(run(&dir, &args, &[]).status.code(), Some(1));
+    let larger = [&args[..], &["--width", "15", "--height", "15"]].concat();
+    assert!(run(&dir, &larger, &[]).status.success());
+    fs::remove_dir_all(&dir).unwrap();
+}